@@ -0,0 +1,142 @@
+use knative_wasm_sdk::{http_handler, Error, Request, Response, Result, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[http_handler]
+fn handle(req: Request) -> Result<Response> {
+    Router::new().post("/validate", validate).handle(req)
+}
+
+/// Environment variable with the labels every admitted object must have,
+/// like `app,team`.
+const REQUIRED_LABELS_ENV: &str = "REQUIRED_LABELS";
+
+const API_VERSION: &str = "admission.k8s.io/v1";
+
+/// The `admission.k8s.io/v1` AdmissionReview, limited to the fields used here.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AdmissionReview {
+    api_version: String,
+    kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request: Option<AdmissionRequest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response: Option<AdmissionResponse>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AdmissionRequest {
+    uid: String,
+    #[serde(default)]
+    object: Value,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AdmissionResponse {
+    uid: String,
+    allowed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<Status>,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Status {
+    code: u16,
+    message: String,
+}
+
+/**
+Validate the object in the posted AdmissionReview, denying it unless it has
+all the labels listed in the REQUIRED_LABELS environment variable.
+ */
+fn validate(req: Request) -> Result<Response> {
+    let review: AdmissionReview = req.json()?;
+    if review.api_version != API_VERSION || review.kind != "AdmissionReview" {
+        return Err(Error::bad_request(format!(
+            "expected an {} AdmissionReview, got {} {}",
+            API_VERSION, review.api_version, review.kind
+        )));
+    }
+    let request = review
+        .request
+        .ok_or_else(|| Error::bad_request("AdmissionReview without a request"))?;
+
+    let required = std::env::var(REQUIRED_LABELS_ENV).unwrap_or_default();
+    let required: Vec<&str> = required
+        .split(',')
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    Response::json(&AdmissionReview {
+        api_version: API_VERSION.to_string(),
+        kind: "AdmissionReview".to_string(),
+        request: None,
+        response: Some(review_object(request, &required)),
+    })
+}
+
+fn review_object(request: AdmissionRequest, required: &[&str]) -> AdmissionResponse {
+    let labels = request.object.pointer("/metadata/labels");
+    let missing: Vec<&str> = required
+        .iter()
+        .copied()
+        .filter(|l| labels.and_then(|labels| labels.get(l)).is_none())
+        .collect();
+
+    let status = (!missing.is_empty()).then(|| Status {
+        code: 403,
+        message: format!("missing required labels: {}", missing.join(", ")),
+    });
+    AdmissionResponse {
+        uid: request.uid,
+        allowed: missing.is_empty(),
+        status,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use knative_wasm_sdk::Method;
+    use serde_json::json;
+
+    fn request(object: Value) -> AdmissionRequest {
+        AdmissionRequest {
+            uid: "705ab4f5-6393-11e8-b7cc-42010a800002".to_string(),
+            object,
+        }
+    }
+
+    #[test]
+    fn test_review_object() {
+        let object = json!({"metadata": {"name": "web", "labels": {"app": "web"}}});
+
+        let resp = review_object(request(object.clone()), &["app"]);
+        assert!(resp.allowed);
+        assert_eq!(resp.status, None);
+
+        let resp = review_object(request(object), &["app", "team", "tier"]);
+        assert!(!resp.allowed);
+        assert_eq!(resp.uid, "705ab4f5-6393-11e8-b7cc-42010a800002");
+        assert_eq!(
+            resp.status,
+            Some(Status {
+                code: 403,
+                message: "missing required labels: team, tier".to_string(),
+            })
+        );
+
+        assert!(!review_object(request(json!({})), &["app"]).allowed);
+    }
+
+    #[test]
+    fn test_validate_rejects_other_kinds() {
+        let body = json!({"apiVersion": "v1", "kind": "Pod"}).to_string();
+        let req = Request::new(Method::Post, "/validate").with_body(body);
+        assert_eq!(validate(req).unwrap_err().status(), 400);
+    }
+}
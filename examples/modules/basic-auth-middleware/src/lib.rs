@@ -0,0 +1,151 @@
+mod bindings {
+    #![allow(clippy::missing_safety_doc)]
+
+    wit_bindgen::generate!({
+        world: "basic-auth-middleware",
+        path: "wit",
+    });
+}
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bindings::exports::wasi::http::incoming_handler::Guest;
+use bindings::wasi::cli::environment;
+use bindings::wasi::http::incoming_handler as downstream;
+use bindings::wasi::http::types::{
+    Fields, IncomingRequest, OutgoingBody, OutgoingResponse, ResponseOutparam,
+};
+use sha2::{Digest, Sha256};
+
+struct Middleware;
+
+impl Guest for Middleware {
+    fn handle(request: IncomingRequest, response_out: ResponseOutparam) {
+        let env = environment::get_environment();
+        let users = parse_users(&env_var(&env, USERS_ENV));
+        let realm = env_var(&env, REALM_ENV);
+        let realm = if realm.is_empty() {
+            DEFAULT_REALM
+        } else {
+            &realm
+        };
+
+        let authorization = request
+            .headers()
+            .get(&"authorization".to_string())
+            .into_iter()
+            .find_map(|v| String::from_utf8(v).ok());
+        if authorized(&users, authorization.as_deref()) {
+            downstream::handle(request, response_out);
+        } else {
+            unauthorized(realm, response_out);
+        }
+    }
+}
+
+bindings::export!(Middleware with_types_in bindings);
+
+/// Environment variable with the accepted credentials, like
+/// `alice:secret,bob:hunter2`.
+const USERS_ENV: &str = "BASIC_AUTH_USERS";
+const REALM_ENV: &str = "BASIC_AUTH_REALM";
+const DEFAULT_REALM: &str = "knative";
+
+fn env_var(env: &[(String, String)], name: &str) -> String {
+    env.iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.clone())
+        .unwrap_or_default()
+}
+
+fn parse_users(spec: &str) -> Vec<(String, String)> {
+    spec.split(',')
+        .filter_map(|entry| entry.trim().split_once(':'))
+        .map(|(user, pass)| (user.to_string(), pass.to_string()))
+        .collect()
+}
+
+/**
+Check the `Authorization` header against the configured users. With no
+users configured every request is rejected, so a misconfigured deployment
+fails closed.
+ */
+fn authorized(users: &[(String, String)], authorization: Option<&str>) -> bool {
+    let Some(credentials) = authorization
+        .and_then(|v| v.trim().split_once(' '))
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("basic"))
+        .and_then(|(_, v)| STANDARD.decode(v.trim()).ok())
+    else {
+        return false;
+    };
+    let given = Sha256::digest(&credentials);
+    users.iter().fold(false, |found, (user, pass)| {
+        let expected = Sha256::digest(format!("{}:{}", user, pass));
+        digests_eq(&expected, &given) | found
+    })
+}
+
+/// Compare fixed-length digests without bailing out on the first difference.
+/// Together with checking every user, this keeps the timing independent of
+/// how close a guess was, though not of the number of configured users.
+fn digests_eq(a: &[u8], b: &[u8]) -> bool {
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn unauthorized(realm: &str, response_out: ResponseOutparam) {
+    let headers = Fields::from_list(&[
+        (
+            "www-authenticate".to_string(),
+            format!("Basic realm=\"{}\"", realm).into_bytes(),
+        ),
+        ("content-type".to_string(), b"text/plain".to_vec()),
+    ])
+    .unwrap_or_else(|_| Fields::new());
+    let resp = OutgoingResponse::new(headers);
+    resp.set_status_code(401).unwrap();
+    let body = resp.body().unwrap();
+
+    ResponseOutparam::set(response_out, Ok(resp));
+
+    let out = body.write().unwrap();
+    if let Err(err) = out.blocking_write_and_flush(b"Unauthorized") {
+        eprintln!("Failed to write the response: {:?}", err);
+    }
+    drop(out);
+    OutgoingBody::finish(body, None).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_users() {
+        assert_eq!(
+            parse_users("alice:secret, bob:a:b,broken"),
+            vec![
+                ("alice".to_string(), "secret".to_string()),
+                ("bob".to_string(), "a:b".to_string()),
+            ]
+        );
+        assert!(parse_users("").is_empty());
+    }
+
+    #[test]
+    fn test_authorized() {
+        let users = parse_users("alice:secret");
+        let basic = |creds: &str| format!("Basic {}", STANDARD.encode(creds));
+
+        assert!(authorized(&users, Some(&basic("alice:secret"))));
+        assert!(authorized(
+            &users,
+            Some(&basic("alice:secret").replace("Basic", "basic"))
+        ));
+        assert!(!authorized(&users, Some(&basic("alice:wrong"))));
+        assert!(!authorized(&users, Some(&basic("alice:secret2"))));
+        assert!(!authorized(&users, Some("Bearer abc")));
+        assert!(!authorized(&users, Some("Basic !!!")));
+        assert!(!authorized(&users, None));
+        assert!(!authorized(&[], Some(&basic(":"))));
+    }
+}
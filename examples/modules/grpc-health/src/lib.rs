@@ -0,0 +1,250 @@
+use knative_wasm_sdk::{http_handler, io, Error, Request, Response, Result, Router};
+use prost::Message;
+use serde::Serialize;
+use url::Url;
+use wasi::http::outgoing_handler;
+use wasi::http::types::{Fields, IncomingBody, IncomingResponse, Method, OutgoingBody};
+
+#[http_handler]
+fn handle(req: Request) -> Result<Response> {
+    Router::new().get("/", check).handle(req)
+}
+
+/// Environment variable with the default upstream, like `http://my-svc:50051`.
+const TARGET_ENV: &str = "GRPC_TARGET";
+
+const CHECK_PATH: &str = "/grpc.health.v1.Health/Check";
+
+/// `grpc.health.v1.HealthCheckRequest`
+#[derive(Clone, PartialEq, Message)]
+struct HealthCheckRequest {
+    #[prost(string, tag = "1")]
+    service: String,
+}
+
+/// `grpc.health.v1.HealthCheckResponse`
+#[derive(Clone, PartialEq, Message)]
+struct HealthCheckResponse {
+    #[prost(int32, tag = "1")]
+    status: i32,
+}
+
+#[derive(Serialize)]
+struct Checked {
+    target: String,
+    service: String,
+    status: &'static str,
+}
+
+/**
+Make a unary `grpc.health.v1.Health/Check` call to the upstream given in the
+"target" query parameter (or the GRPC_TARGET environment variable), over the
+host's `wasi:http/outgoing-handler`, and report the serving status as JSON.
+
+gRPC needs HTTP/2, which a guest can't ask for: the host's outgoing handler
+has to speak h2 to the upstream (negotiated via ALPN for https, or h2c with
+prior knowledge for http). Wasmtime's default handler only speaks HTTP/1.1,
+so gRPC servers reject the call there. The guest also can't send
+`te: trailers`, which wasi-http hosts forbid, so servers insisting on it
+reject the call too.
+ */
+fn check(req: Request) -> Result<Response> {
+    let target = req
+        .query("target")
+        .map(String::from)
+        .or_else(|| std::env::var(TARGET_ENV).ok())
+        .ok_or_else(|| Error::bad_request("missing \"target\" query parameter"))?;
+    let url = Url::parse(&target)
+        .map_err(|err| Error::bad_request(format!("invalid target {:?}: {}", target, err)))?;
+    let service = req.query("service").unwrap_or_default().to_string();
+
+    let message = HealthCheckRequest {
+        service: service.clone(),
+    };
+    let reply: HealthCheckResponse = decode(&unary_call(&url, CHECK_PATH, &encode(&message))?)?;
+
+    Response::json(&Checked {
+        target,
+        service,
+        status: serving_status(reply.status),
+    })
+}
+
+/// Wrap the message in a gRPC length-prefixed frame, uncompressed.
+fn encode<M: Message>(message: &M) -> Vec<u8> {
+    let payload = message.encode_to_vec();
+    let mut frame = Vec::with_capacity(5 + payload.len());
+    frame.push(0);
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&payload);
+    frame
+}
+
+/// Decode a single length-prefixed, uncompressed gRPC frame.
+fn decode<M: Message + Default>(frame: &[u8]) -> Result<M> {
+    let bad = |msg: &str| Error::new(502, format!("invalid gRPC response: {}", msg));
+    if frame.len() < 5 {
+        return Err(bad("frame too short"));
+    }
+    if frame[0] != 0 {
+        return Err(bad("compressed messages are not supported"));
+    }
+    let len = u32::from_be_bytes([frame[1], frame[2], frame[3], frame[4]]) as usize;
+    let payload = 5usize
+        .checked_add(len)
+        .and_then(|end| frame.get(5..end))
+        .ok_or_else(|| bad("truncated frame"))?;
+    M::decode(payload).map_err(|err| bad(&err.to_string()))
+}
+
+fn serving_status(status: i32) -> &'static str {
+    match status {
+        1 => "SERVING",
+        2 => "NOT_SERVING",
+        3 => "SERVICE_UNKNOWN",
+        _ => "UNKNOWN",
+    }
+}
+
+/**
+POST the framed message to the gRPC method and return the response frame,
+after checking the `grpc-status` from the trailers (or from the headers, for
+trailers-only responses).
+ */
+fn unary_call(url: &Url, method: &str, frame: &[u8]) -> Result<Vec<u8>> {
+    let headers = Fields::from_list(&[("content-type".to_string(), b"application/grpc".to_vec())])
+        .map_err(|err| Error::internal(format!("invalid headers: {:?}", err)))?;
+    let url = url
+        .join(method)
+        .map_err(|err| Error::bad_request(format!("invalid target {}: {}", url, err)))?;
+    let req = io::outgoing_request(&url, headers)?;
+    req.set_method(&Method::Post)
+        .map_err(|_| Error::internal("setting the request method"))?;
+
+    let body = req.body().unwrap();
+    let future = outgoing_handler::handle(req, None)
+        .map_err(|code| Error::new(502, format!("sending request: {:?}", code)))?;
+    let out = body.write().unwrap();
+    io::write_all(&out, frame)?;
+    drop(out);
+    OutgoingBody::finish(body, None)
+        .map_err(|code| Error::new(502, format!("sending request: {:?}", code)))?;
+
+    let resp = io::wait_response(&future)?;
+
+    if resp.status() != 200 {
+        return Err(Error::new(
+            502,
+            format!("upstream responded with HTTP {}", resp.status()),
+        ));
+    }
+    let header_status = grpc_status(&resp.headers().entries());
+    let (frame, trailers) = read_body(&resp)?;
+    match header_status.or_else(|| grpc_status(&trailers)) {
+        Some((0, _)) => Ok(frame),
+        Some((code, message)) => Err(Error::new(
+            502,
+            format!("gRPC status {}: {}", code, message),
+        )),
+        None => Err(Error::new(502, "gRPC response without grpc-status")),
+    }
+}
+
+/// Header or trailer names and values.
+type Entries = Vec<(String, Vec<u8>)>;
+
+/// Read the whole body, then wait for the trailers that follow it.
+fn read_body(resp: &IncomingResponse) -> Result<(Vec<u8>, Entries)> {
+    let body = resp
+        .consume()
+        .map_err(|_| Error::internal("upstream body already consumed"))?;
+    let stream = body
+        .stream()
+        .map_err(|_| Error::internal("upstream body stream already taken"))?;
+    let bytes = io::read_all(&stream)?;
+    drop(stream);
+
+    let trailers = io::wait_trailers(&IncomingBody::finish(body))?;
+
+    Ok((bytes, trailers.map(|t| t.entries()).unwrap_or_default()))
+}
+
+/// The `grpc-status` code and the `grpc-message`, which gRPC sends
+/// percent-encoded.
+fn grpc_status(fields: &[(String, Vec<u8>)]) -> Option<(u32, String)> {
+    let value = |name: &str| {
+        fields
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_slice())
+    };
+    let code = String::from_utf8_lossy(value("grpc-status")?)
+        .trim()
+        .parse()
+        .ok()?;
+    let message = value("grpc-message")
+        .map(|v| String::from_utf8_lossy(&urlencoding::decode_binary(v)).into_owned())
+        .unwrap_or_default();
+    Some((code, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let msg = HealthCheckRequest {
+            service: "echo".to_string(),
+        };
+        let frame = encode(&msg);
+        assert_eq!(frame, [0, 0, 0, 0, 6, 10, 4, b'e', b'c', b'h', b'o']);
+        assert_eq!(decode::<HealthCheckRequest>(&frame).unwrap(), msg);
+
+        assert_eq!(
+            decode::<HealthCheckResponse>(&frame[..3])
+                .unwrap_err()
+                .status(),
+            502
+        );
+        assert_eq!(
+            decode::<HealthCheckResponse>(&frame[..8])
+                .unwrap_err()
+                .status(),
+            502
+        );
+        assert_eq!(
+            decode::<HealthCheckResponse>(&[0, 0xff, 0xff, 0xff, 0xff])
+                .unwrap_err()
+                .status(),
+            502
+        );
+    }
+
+    #[test]
+    fn test_grpc_status() {
+        let fields = vec![
+            ("Grpc-Status".to_string(), b"5".to_vec()),
+            ("grpc-message".to_string(), b"not found".to_vec()),
+        ];
+        assert_eq!(grpc_status(&fields), Some((5, "not found".to_string())));
+        let fields = vec![
+            ("grpc-status".to_string(), b"14".to_vec()),
+            (
+                "grpc-message".to_string(),
+                b"caf%C3%A9 is 50%25 down".to_vec(),
+            ),
+        ];
+        assert_eq!(
+            grpc_status(&fields),
+            Some((14, "caf\u{e9} is 50% down".to_string()))
+        );
+        assert_eq!(grpc_status(&[]), None);
+    }
+
+    #[test]
+    fn test_serving_status() {
+        assert_eq!(serving_status(1), "SERVING");
+        assert_eq!(serving_status(42), "UNKNOWN");
+    }
+}
@@ -0,0 +1,64 @@
+use knative_wasm_sdk::{http_handler, Error, Request, Response, Result, Router};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+#[http_handler]
+fn handle(req: Request) -> Result<Response> {
+    Router::new().get("/", hash_loop).handle(req)
+}
+
+const DEFAULT_ITERATIONS: u64 = 100_000;
+
+#[derive(Serialize)]
+struct Hashed {
+    iterations: u64,
+    digest: String,
+}
+
+/**
+Hash the "seed" query parameter with SHA-256, feeding each digest back in,
+"iterations" times. The work grows linearly with the iterations, which makes
+it easy to exhaust fuel or hit epoch deadlines on purpose.
+ */
+fn hash_loop(req: Request) -> Result<Response> {
+    let iterations = match req.query("iterations") {
+        None => DEFAULT_ITERATIONS,
+        Some(v) => v
+            .parse()
+            .map_err(|_| Error::bad_request(format!("invalid iterations {:?}", v)))?,
+    }
+    .max(1);
+    let seed = req.query("seed").unwrap_or_default();
+
+    Response::json(&Hashed {
+        iterations,
+        digest: hex(&hash_chain(seed.as_bytes(), iterations)),
+    })
+}
+
+fn hash_chain(seed: &[u8], iterations: u64) -> [u8; 32] {
+    let mut digest: [u8; 32] = Sha256::digest(seed).into();
+    for _ in 1..iterations {
+        digest = Sha256::digest(digest).into();
+    }
+    digest
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_chain() {
+        assert_eq!(
+            hex(&hash_chain(b"", 1)),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        let twice: [u8; 32] = Sha256::digest(hash_chain(b"abc", 1)).into();
+        assert_eq!(hash_chain(b"abc", 2), twice);
+    }
+}
@@ -0,0 +1,158 @@
+use knative_wasm_sdk::{io, Error, Request, Result};
+use url::Url;
+use wasi::http::outgoing_handler;
+use wasi::http::types::{
+    Fields, IncomingBody, IncomingRequest, IncomingResponse, OutgoingBody, OutgoingResponse,
+    ResponseOutparam,
+};
+
+wasi::http::incoming_handler::export!(Fetch);
+
+struct Fetch;
+
+impl exports::wasi::http::incoming_handler::Guest for Fetch {
+    fn handle(request: IncomingRequest, response_out: ResponseOutparam) {
+        let upstream = match forward(request) {
+            Ok(upstream) => upstream,
+            Err(err) => return send_error(err, response_out),
+        };
+        if let Err(err) = stream_back(upstream, response_out) {
+            eprintln!("Streaming the upstream response failed: {}", err);
+        }
+    }
+}
+
+fn send_error(err: Error, response_out: ResponseOutparam) {
+    if let Err(err) = err.into_response().send(response_out) {
+        eprintln!("Failed to send the response: {}", err);
+    }
+}
+
+/// Hop-by-hop headers, which only make sense on a single connection and
+/// must not be forwarded by proxies (RFC 9110, section 7.6.1).
+const HOP_BY_HOP: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "proxy-connection",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+    "host",
+];
+
+/**
+Forward the incoming request, with its method, end-to-end headers and body,
+to the URL given in the "url" query parameter, using the host's
+`wasi:http/outgoing-handler`.
+ */
+fn forward(incoming: IncomingRequest) -> Result<IncomingResponse> {
+    let pq = incoming.path_with_query().unwrap_or_default();
+    let url = target_url(&Request::new(incoming.method().into(), &pq))?;
+
+    let headers = Fields::from_list(&forwarded_headers(incoming.headers().entries()))
+        .map_err(|err| Error::bad_request(format!("invalid headers: {:?}", err)))?;
+    let req = io::outgoing_request(&url, headers)?;
+    req.set_method(&incoming.method())
+        .map_err(|_| Error::bad_request("unsupported method"))?;
+
+    let out_body = req.body().unwrap();
+    let future = outgoing_handler::handle(req, None)
+        .map_err(|code| Error::new(502, format!("sending request: {:?}", code)))?;
+
+    let in_body = incoming
+        .consume()
+        .map_err(|_| Error::internal("request body already consumed"))?;
+    let from = in_body.stream().unwrap();
+    let to = out_body.write().unwrap();
+    io::copy(&from, &to)?;
+    drop((from, to));
+    IncomingBody::finish(in_body);
+    OutgoingBody::finish(out_body, None)
+        .map_err(|code| Error::new(502, format!("sending request: {:?}", code)))?;
+
+    io::wait_response(&future)
+}
+
+/**
+Stream the upstream response back to the client as it arrives.
+ */
+fn stream_back(upstream: IncomingResponse, response_out: ResponseOutparam) -> Result<()> {
+    let resp = match response_head(&upstream) {
+        Ok(resp) => resp,
+        Err(err) => {
+            send_error(err, response_out);
+            return Ok(());
+        }
+    };
+    let out_body = resp.body().unwrap();
+
+    ResponseOutparam::set(response_out, Ok(resp));
+
+    let in_body = upstream
+        .consume()
+        .map_err(|_| Error::internal("upstream body already consumed"))?;
+    let from = in_body.stream().unwrap();
+    let to = out_body.write().unwrap();
+    io::copy(&from, &to)?;
+    drop((from, to));
+    IncomingBody::finish(in_body);
+
+    OutgoingBody::finish(out_body, None)
+        .map_err(|err| Error::internal(format!("finishing body: {:?}", err)))
+}
+
+fn response_head(upstream: &IncomingResponse) -> Result<OutgoingResponse> {
+    let headers = Fields::from_list(&forwarded_headers(upstream.headers().entries()))
+        .map_err(|err| Error::new(502, format!("invalid upstream headers: {:?}", err)))?;
+    let resp = OutgoingResponse::new(headers);
+    resp.set_status_code(upstream.status())
+        .map_err(|_| Error::new(502, format!("invalid upstream status: {}", upstream.status())))?;
+    Ok(resp)
+}
+
+fn forwarded_headers(headers: Vec<(String, Vec<u8>)>) -> Vec<(String, Vec<u8>)> {
+    headers
+        .into_iter()
+        .filter(|(name, _)| !HOP_BY_HOP.iter().any(|h| name.eq_ignore_ascii_case(h)))
+        .collect()
+}
+
+fn target_url(req: &Request) -> Result<Url> {
+    let raw = req
+        .query("url")
+        .ok_or_else(|| Error::bad_request("missing \"url\" query parameter"))?;
+    Url::parse(raw).map_err(|err| Error::bad_request(format!("invalid url {:?}: {}", raw, err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use knative_wasm_sdk::Method;
+
+    #[test]
+    fn test_target_url() {
+        let url = |pq| target_url(&Request::new(Method::Get, pq));
+
+        assert_eq!(
+            url("/?url=https%3A%2F%2Fexample.com%2Fa%3Fb%3Dc").unwrap().as_str(),
+            "https://example.com/a?b=c"
+        );
+        assert_eq!(url("/").unwrap_err().status(), 400);
+        assert_eq!(url("/?url=not%20a%20url").unwrap_err().status(), 400);
+    }
+
+    #[test]
+    fn test_forwarded_headers() {
+        let headers = vec![
+            ("Accept".to_string(), b"*/*".to_vec()),
+            ("Connection".to_string(), b"close".to_vec()),
+            ("host".to_string(), b"proxy.local".to_vec()),
+            ("x-request-id".to_string(), b"42".to_vec()),
+        ];
+        let names: Vec<_> = forwarded_headers(headers).into_iter().map(|(n, _)| n).collect();
+        assert_eq!(names, ["Accept", "x-request-id"]);
+    }
+}
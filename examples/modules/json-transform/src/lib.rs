@@ -0,0 +1,122 @@
+use knative_wasm_sdk::{http_handler, Error, Request, Response, Result, Router};
+use serde_json::{Map, Value};
+
+#[http_handler]
+fn handle(req: Request) -> Result<Response> {
+    Router::new().post("/", transform).handle(req)
+}
+
+/// Environment variable holding the field mapping, like
+/// `user.name=name,user.address.city=city`.
+const MAPPING_ENV: &str = "FIELD_MAPPING";
+
+/// A source and a target field, as paths of object keys.
+type Mapping = Vec<(Vec<String>, Vec<String>)>;
+
+/**
+Map fields of the posted JSON object (or of each object in a posted array)
+into a new object, as configured by the FIELD_MAPPING environment variable.
+Without a mapping the input is returned as is.
+ */
+fn transform(req: Request) -> Result<Response> {
+    let mapping = parse_mapping(&std::env::var(MAPPING_ENV).unwrap_or_default())
+        .map_err(|err| Error::internal(format!("invalid {}: {}", MAPPING_ENV, err)))?;
+    let input: Value = req.json()?;
+
+    let output = match input {
+        Value::Array(items) => Value::Array(items.iter().map(|v| apply(&mapping, v)).collect()),
+        Value::Object(_) => apply(&mapping, &input),
+        _ => return Err(Error::bad_request("expected a JSON object or array")),
+    };
+    Response::json(&output)
+}
+
+fn parse_mapping(spec: &str) -> std::result::Result<Mapping, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (from, to) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("entry {:?} is not source=target", entry))?;
+            Ok((path(from)?, path(to)?))
+        })
+        .collect()
+}
+
+fn path(p: &str) -> std::result::Result<Vec<String>, String> {
+    let keys: Vec<String> = p.trim().split('.').map(String::from).collect();
+    if keys.iter().any(String::is_empty) {
+        return Err(format!("invalid field path {:?}", p));
+    }
+    Ok(keys)
+}
+
+fn apply(mapping: &Mapping, input: &Value) -> Value {
+    if mapping.is_empty() {
+        return input.clone();
+    }
+    let mut output = Value::Object(Map::new());
+    for (from, to) in mapping {
+        if let Some(v) = lookup(input, from) {
+            insert(&mut output, to, v.clone());
+        }
+    }
+    output
+}
+
+fn lookup<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |v, key| v.get(key))
+}
+
+fn insert(value: &mut Value, path: &[String], new: Value) {
+    let (last, parents) = path.split_last().expect("paths are never empty");
+    let mut current = value;
+    for key in parents {
+        let obj = ensure_object(current);
+        current = obj
+            .entry(key.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    ensure_object(current).insert(last.clone(), new);
+}
+
+fn ensure_object(value: &mut Value) -> &mut Map<String, Value> {
+    if !value.is_object() {
+        *value = Value::Object(Map::new());
+    }
+    value.as_object_mut().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_mapping() {
+        let mapping = parse_mapping(" user.name=name , id=meta.id,").unwrap();
+        assert_eq!(
+            mapping,
+            vec![
+                (vec!["user".into(), "name".into()], vec!["name".into()]),
+                (vec!["id".into()], vec!["meta".into(), "id".into()]),
+            ]
+        );
+        assert_eq!(parse_mapping("").unwrap(), vec![]);
+        assert!(parse_mapping("user.name").is_err());
+        assert!(parse_mapping("user..name=name").is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let mapping =
+            parse_mapping("user.name=name,user.address.city=location.city,missing=x").unwrap();
+        let input = json!({"user": {"name": "Ada", "address": {"city": "London"}}, "id": 1});
+        assert_eq!(
+            apply(&mapping, &input),
+            json!({"name": "Ada", "location": {"city": "London"}})
+        );
+        assert_eq!(apply(&vec![], &input), input);
+    }
+}
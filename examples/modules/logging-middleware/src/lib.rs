@@ -0,0 +1,62 @@
+mod bindings {
+    #![allow(clippy::missing_safety_doc)]
+
+    wit_bindgen::generate!({
+        world: "logging-middleware",
+        path: "wit",
+    });
+}
+
+use bindings::exports::wasi::http::incoming_handler::Guest;
+use bindings::wasi::clocks::monotonic_clock;
+use bindings::wasi::http::incoming_handler as downstream;
+use bindings::wasi::http::types::{IncomingRequest, Method, ResponseOutparam};
+
+struct Middleware;
+
+impl Guest for Middleware {
+    fn handle(request: IncomingRequest, response_out: ResponseOutparam) {
+        let line = request_line(&request.method(), request.path_with_query().as_deref());
+        let start = monotonic_clock::now();
+
+        downstream::handle(request, response_out);
+
+        let elapsed_ns = monotonic_clock::now() - start;
+        eprintln!("{} handled in {:.3}ms", line, elapsed_ns as f64 / 1e6);
+    }
+}
+
+bindings::export!(Middleware with_types_in bindings);
+
+fn request_line(method: &Method, path_with_query: Option<&str>) -> String {
+    format!("{} {}", method_name(method), path_with_query.unwrap_or("/"))
+}
+
+fn method_name(method: &Method) -> &str {
+    match method {
+        Method::Get => "GET",
+        Method::Head => "HEAD",
+        Method::Post => "POST",
+        Method::Put => "PUT",
+        Method::Delete => "DELETE",
+        Method::Connect => "CONNECT",
+        Method::Options => "OPTIONS",
+        Method::Trace => "TRACE",
+        Method::Patch => "PATCH",
+        Method::Other(m) => m,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_line() {
+        assert_eq!(request_line(&Method::Get, None), "GET /");
+        assert_eq!(
+            request_line(&Method::Other("PURGE".to_string()), Some("/a?b=c")),
+            "PURGE /a?b=c"
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use knative_wasm_sdk::{http_handler, Error, Request, Response, Result, Router};
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+
+#[http_handler]
+fn handle(req: Request) -> Result<Response> {
+    Router::new().post("/", render).handle(req)
+}
+
+/**
+Render the posted Markdown (CommonMark with tables, strikethrough, footnotes
+and task lists) as an HTML fragment. Raw HTML in the input is escaped, and
+link and image targets are limited to relative URLs and the http, https and
+mailto schemes, so the output is safe to embed.
+ */
+fn render(req: Request) -> Result<Response> {
+    match req.content_type() {
+        None => {}
+        Some(ct)
+            if ["text/markdown", "text/plain"]
+                .iter()
+                .any(|t| ct.eq_ignore_ascii_case(t)) => {}
+        Some(ct) => {
+            return Err(Error::new(
+                415,
+                format!("unsupported content type {}, use text/markdown", ct),
+            ))
+        }
+    }
+    if req.preferred(&["text/html"]).is_none() {
+        return Err(Error::new(406, "only text/html responses are available"));
+    }
+
+    Ok(Response::ok()
+        .with_header("content-type", "text/html; charset=utf-8")
+        .with_body(to_html(req.text()?)))
+}
+
+fn to_html(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_TASKLISTS;
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        }),
+        event => event,
+    });
+
+    let mut out = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut out, events);
+    out
+}
+
+const SAFE_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// Replace URLs with a scheme other than the safe ones, like `javascript:`,
+/// with an empty one. Browsers ignore whitespace and control characters in a
+/// scheme, so those are dropped before checking it.
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    let cleaned: String = url
+        .chars()
+        .take_while(|c| !matches!(c, '/' | '?' | '#'))
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+        .collect();
+    match cleaned.split_once(':') {
+        Some((scheme, _)) if !SAFE_SCHEMES.iter().any(|s| scheme.eq_ignore_ascii_case(s)) => {
+            CowStr::Borrowed("")
+        }
+        _ => url,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use knative_wasm_sdk::Method;
+
+    #[test]
+    fn test_to_html() {
+        assert_eq!(
+            to_html("# Title\n\nSome *emphasis* and ~~strike~~."),
+            "<h1>Title</h1>\n<p>Some <em>emphasis</em> and <del>strike</del>.</p>\n"
+        );
+        assert_eq!(
+            to_html("<script>alert(1)</script>"),
+            "&lt;script&gt;alert(1)&lt;/script&gt;"
+        );
+    }
+
+    #[test]
+    fn test_to_html_filters_urls() {
+        assert_eq!(
+            to_html("[x](javascript:alert(1)) ![i](JavaScript:alert(2)) <vbscript:x>"),
+            "<p><a href=\"\">x</a> <img src=\"\" alt=\"i\" /> <a href=\"\">vbscript:x</a></p>\n"
+        );
+        assert_eq!(
+            to_html("[a](https://example.com/a:b) [b](/docs?q=1:2) [c](mailto:me@example.com)"),
+            "<p><a href=\"https://example.com/a:b\">a</a> <a href=\"/docs?q=1:2\">b</a> \
+             <a href=\"mailto:me@example.com\">c</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_safe_url() {
+        assert_eq!(&*safe_url("java\tscript:alert(1)".into()), "");
+        assert_eq!(&*safe_url("data:text/html,x".into()), "");
+        assert_eq!(
+            &*safe_url("HTTPS://example.com".into()),
+            "HTTPS://example.com"
+        );
+        assert_eq!(&*safe_url("page.html#a:b".into()), "page.html#a:b");
+    }
+
+    #[test]
+    fn test_render() {
+        let post = |ct: &str| {
+            Request::new(Method::Post, "/")
+                .with_header("content-type", ct)
+                .with_body("*hi*")
+        };
+
+        let resp = render(post("text/markdown; charset=utf-8")).unwrap();
+        assert_eq!(
+            resp.header("content-type"),
+            Some("text/html; charset=utf-8")
+        );
+        assert_eq!(resp.body(), b"<p><em>hi</em></p>\n");
+
+        assert_eq!(render(post("application/json")).unwrap_err().status(), 415);
+        let req = post("text/markdown").with_header("accept", "application/json");
+        assert_eq!(render(req).unwrap_err().status(), 406);
+    }
+}
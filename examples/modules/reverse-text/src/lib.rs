@@ -1,52 +1,66 @@
-use wasi::http::types::{
-    Fields, IncomingRequest, OutgoingBody, OutgoingResponse, ResponseOutparam,
-};
-use std::collections::HashMap;
+use knative_wasm_sdk::{http_handler, Error, Method, Request, Response, Result, Router};
+use serde::{Deserialize, Serialize};
 
-wasi::http::incoming_handler::export!(Reverse);
-
-struct Reverse;
+#[http_handler]
+fn handle(req: Request) -> Result<Response> {
+    Router::new()
+        .get("/*", reverse)
+        .post("/*", reverse)
+        .handle(req)
+}
 
-impl exports::wasi::http::incoming_handler::Guest for Reverse {
-    fn handle(request: IncomingRequest, response_out: ResponseOutparam) {
-        let resp = OutgoingResponse::new(Fields::new());
-        let body = resp.body().unwrap();
+const TEXT: &str = "text/plain";
+const JSON: &str = "application/json";
 
-        ResponseOutparam::set(response_out, Ok(resp));
+/// JSON form of both the request and the response body.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Text {
+    text: String,
+}
 
-        let pq = request.path_with_query().unwrap();
-        let input = fetch_text_query_param(pq);
-        let value = reverse_text(input);
+fn reverse(req: Request) -> Result<Response> {
+    let input = match req.method() {
+        Method::Post => fetch_text_body(&req)?,
+        _ => fetch_text_query_param(&req),
+    };
+    respond(&req, reverse_text(input))
+}
 
-        let out = body.write().unwrap();
-        out.blocking_write_and_flush(value.as_bytes()).unwrap();
-        drop(out);
+/**
+Get query parameter named "text", or return "Hello, WASI!" if
+it's not present
+ */
+fn fetch_text_query_param(req: &Request) -> String {
+    req.query("text").unwrap_or("Hello, WASI!").to_string()
+}
 
-        OutgoingBody::finish(body, None).unwrap();
+/**
+Get the text from a POST body, either as plain text, or as a JSON object
+with a "text" field when the body is sent as JSON
+ */
+fn fetch_text_body(req: &Request) -> Result<String> {
+    match req.content_type() {
+        Some(ct) if ct.eq_ignore_ascii_case(JSON) => Ok(req.json::<Text>()?.text),
+        Some(ct) if !ct.eq_ignore_ascii_case(TEXT) => Err(Error::new(
+            415,
+            format!("unsupported content type {}, use {} or {}", ct, TEXT, JSON),
+        )),
+        _ => Ok(req.text()?.to_string()),
     }
 }
 
 /**
-Get query parameter named "text", or return "Hello, WASI!" if
-it's not present
+Respond with plain text or JSON, depending on what the client accepts
  */
-fn fetch_text_query_param(pq: String) -> String {
-    urlencoding::decode(&pq)
-        .unwrap()
-        .split_once("?")
-        .and_then(|(_, s)| {
-            return Some(querystring::querify(s));
-        })
-        .map(|q| {
-            HashMap::from_iter(q)
-        })
-        .and_then(|q: HashMap<&str, &str>| {
-            q.get("text").cloned()
-        })
-        .map(|s| {
-            s.to_string()
-        })
-        .unwrap_or("Hello, WASI!".to_string())
+fn respond(req: &Request, text: String) -> Result<Response> {
+    match req.preferred(&[TEXT, JSON]) {
+        Some(JSON) => Response::json(&Text { text }),
+        Some(_) => Ok(Response::text(text)),
+        None => Err(Error::new(
+            406,
+            format!("only {} and {} responses are available", TEXT, JSON),
+        )),
+    }
 }
 
 fn reverse_text(str: String) -> String {
@@ -57,12 +71,67 @@ fn reverse_text(str: String) -> String {
 mod tests {
     use super::*;
 
+    fn get(pq: &str) -> Request {
+        Request::new(Method::Get, pq)
+    }
+
+    fn post(content_type: &str, body: &str) -> Request {
+        Request::new(Method::Post, "/")
+            .with_header("content-type", content_type)
+            .with_body(body)
+    }
+
     #[test]
     fn test_fetch_text_query_param() {
-        assert_eq!(fetch_text_query_param("".to_string()), "Hello, WASI!");
-        assert_eq!(fetch_text_query_param("?".to_string()), "Hello, WASI!");
-        assert_eq!(fetch_text_query_param("?text=Hello".to_string()), "Hello");
-        assert_eq!(fetch_text_query_param("?text=Happy%20testing".to_string()), "Happy testing");
+        assert_eq!(fetch_text_query_param(&get("")), "Hello, WASI!");
+        assert_eq!(fetch_text_query_param(&get("?")), "Hello, WASI!");
+        assert_eq!(fetch_text_query_param(&get("?text=Hello")), "Hello");
+        assert_eq!(
+            fetch_text_query_param(&get("?text=Happy%20testing")),
+            "Happy testing"
+        );
+    }
+
+    #[test]
+    fn test_fetch_text_body() {
+        assert_eq!(
+            fetch_text_body(&post("text/plain", "Hello")).unwrap(),
+            "Hello"
+        );
+        assert_eq!(
+            fetch_text_body(&post("application/json", r#"{"text": "Hello"}"#)).unwrap(),
+            "Hello"
+        );
+        assert_eq!(
+            fetch_text_body(&post("application/json", "Hello"))
+                .unwrap_err()
+                .status(),
+            400
+        );
+        assert_eq!(
+            fetch_text_body(&post("image/png", "Hello"))
+                .unwrap_err()
+                .status(),
+            415
+        );
+    }
+
+    #[test]
+    fn test_respond() {
+        let resp = respond(&get("/"), "cba".to_string()).unwrap();
+        assert_eq!(
+            resp.header("content-type"),
+            Some("text/plain; charset=utf-8")
+        );
+        assert_eq!(resp.body(), b"cba");
+
+        let req = get("/").with_header("accept", "application/json");
+        let resp = respond(&req, "cba".to_string()).unwrap();
+        assert_eq!(resp.header("content-type"), Some("application/json"));
+        assert_eq!(resp.body(), br#"{"text":"cba"}"#);
+
+        let req = get("/").with_header("accept", "image/png");
+        assert_eq!(respond(&req, "cba".to_string()).unwrap_err().status(), 406);
     }
 
     #[test]
@@ -0,0 +1,115 @@
+use knative_wasm_sdk::{http_handler, io, Error, Request, Response, Result, Router};
+use wasi::clocks::{monotonic_clock, wall_clock};
+
+#[http_handler]
+fn handle(req: Request) -> Result<Response> {
+    Router::new().get("/", clock).handle(req)
+}
+
+const DEFAULT_COUNT: u32 = 10;
+const MAX_COUNT: u32 = 3600;
+const DEFAULT_INTERVAL_MS: u64 = 1000;
+const MAX_INTERVAL_MS: u64 = 60_000;
+/// Longest a single stream may last, whatever the count and interval.
+const MAX_DURATION_MS: u64 = 3_600_000;
+
+/**
+Stream the current wall-clock time as server-sent events, "count" times,
+every "interval" milliseconds. Used to check that responses aren't buffered
+or cut short by the host.
+ */
+fn clock(req: Request) -> Result<Response> {
+    let (count, interval_ms) = schedule(&req)?;
+
+    Ok(Response::ok()
+        .with_header("content-type", "text/event-stream")
+        .with_header("cache-control", "no-cache")
+        .with_writer(move |out| {
+            for seq in 1..=count {
+                if seq > 1 {
+                    monotonic_clock::subscribe_duration(interval_ms * 1_000_000).block();
+                }
+                let now = wall_clock::now();
+                let millis = now.seconds * 1000 + u64::from(now.nanoseconds / 1_000_000);
+                io::write_all(out, event(seq, millis).as_bytes())?;
+            }
+            Ok(())
+        }))
+}
+
+/// The requested event count and interval, capped so a stream lasts at most
+/// `MAX_DURATION_MS`.
+fn schedule(req: &Request) -> Result<(u32, u64)> {
+    let interval_ms = param(req, "interval", DEFAULT_INTERVAL_MS)?.min(MAX_INTERVAL_MS);
+    let max_count = match MAX_DURATION_MS.checked_div(interval_ms) {
+        Some(ticks) => u32::try_from(ticks + 1).unwrap_or(MAX_COUNT),
+        None => MAX_COUNT,
+    };
+    let count = param(req, "count", DEFAULT_COUNT)?
+        .min(MAX_COUNT)
+        .min(max_count);
+    Ok((count, interval_ms))
+}
+
+fn param<T: std::str::FromStr>(req: &Request, name: &str, default: T) -> Result<T> {
+    match req.query(name) {
+        None => Ok(default),
+        Some(v) => v
+            .parse()
+            .map_err(|_| Error::bad_request(format!("invalid {} {:?}", name, v))),
+    }
+}
+
+fn event(seq: u32, unix_millis: u64) -> String {
+    format!(
+        "id: {}\nevent: tick\ndata: {{\"seq\":{},\"unixMillis\":{}}}\n\n",
+        seq, seq, unix_millis
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use knative_wasm_sdk::Method;
+
+    #[test]
+    fn test_param() {
+        let req = Request::new(Method::Get, "/?count=3&interval=x");
+        assert_eq!(param(&req, "count", DEFAULT_COUNT), Ok(3));
+        assert_eq!(param(&req, "missing", 7u64), Ok(7));
+        assert_eq!(
+            param(&req, "interval", DEFAULT_INTERVAL_MS)
+                .unwrap_err()
+                .status(),
+            400
+        );
+    }
+
+    #[test]
+    fn test_schedule() {
+        let schedule = |pq| schedule(&Request::new(Method::Get, pq));
+        assert_eq!(schedule("/"), Ok((DEFAULT_COUNT, DEFAULT_INTERVAL_MS)));
+        assert_eq!(schedule("/?count=5&interval=250"), Ok((5, 250)));
+        assert_eq!(
+            schedule("/?count=99999&interval=18446744073709551615"),
+            Ok((61, MAX_INTERVAL_MS))
+        );
+        assert_eq!(schedule("/?count=3600&interval=0"), Ok((MAX_COUNT, 0)));
+        for pq in ["/?count=3600&interval=1001", "/?count=3600&interval=60000"] {
+            let (count, interval_ms) = schedule(pq).unwrap();
+            assert!(
+                u64::from(count - 1) * interval_ms <= MAX_DURATION_MS,
+                "{}",
+                pq
+            );
+        }
+    }
+
+    #[test]
+    fn test_event() {
+        assert_eq!(
+            event(2, 1700000000123),
+            "id: 2\nevent: tick\ndata: {\"seq\":2,\"unixMillis\":1700000000123}\n\n"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+
+use knative_wasm_sdk::{http_handler, Error, Request, Response, Result, Router};
+use minijinja::{context, path_loader, Environment, ErrorKind};
+
+#[http_handler]
+fn handle(req: Request) -> Result<Response> {
+    Router::new()
+        .get("/", render)
+        .get("/:page", render)
+        .handle(req)
+}
+
+/// Environment variable with the directory templates are mounted at.
+const TEMPLATES_ENV: &str = "TEMPLATES_DIR";
+const DEFAULT_TEMPLATES_DIR: &str = "/templates";
+
+/// Served when no `index.html` is mounted, so the module works standalone.
+const DEFAULT_INDEX: &str = include_str!("../templates/index.html");
+
+/**
+Render the `<page>.html` template (`index.html` for the root) from the
+mounted templates directory, with the query parameters as the `query`
+variable.
+ */
+fn render(req: Request) -> Result<Response> {
+    let dir = std::env::var(TEMPLATES_ENV).unwrap_or_else(|_| DEFAULT_TEMPLATES_DIR.to_string());
+    let page = req.param("page").unwrap_or("index");
+    let query: BTreeMap<&str, &str> = req
+        .query_pairs()
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+
+    let html = render_page(&environment(&dir), page, context! { query })?;
+    Ok(Response::ok()
+        .with_header("content-type", "text/html; charset=utf-8")
+        .with_body(html))
+}
+
+fn environment(dir: &str) -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_loader(path_loader(dir));
+    env
+}
+
+fn render_page(env: &Environment, page: &str, ctx: minijinja::Value) -> Result<String> {
+    let name = format!("{}.html", page);
+    let tmpl = match env.get_template(&name) {
+        Ok(tmpl) => tmpl,
+        Err(err) if err.kind() == ErrorKind::TemplateNotFound && page == "index" => env
+            .template_from_named_str("index.html", DEFAULT_INDEX)
+            .map_err(|err| Error::internal(format!("loading {}: {}", name, err)))?,
+        Err(err) if err.kind() == ErrorKind::TemplateNotFound => {
+            return Err(Error::not_found(format!("no template for {}", page)));
+        }
+        Err(err) => return Err(Error::internal(format!("loading {}: {}", name, err))),
+    };
+    tmpl.render(ctx)
+        .map_err(|err| Error::internal(format!("rendering {}: {}", name, err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_default_index() {
+        let env = environment("/nonexistent");
+        let html = render_page(
+            &env,
+            "index",
+            context! { query => BTreeMap::from([("name", "<Knative>")]) },
+        )
+        .unwrap();
+        assert!(
+            html.contains("<h1>Hello, &lt;Knative&gt;!</h1>"),
+            "{}",
+            html
+        );
+        assert!(html.contains("<title>Knative WASM</title>"), "{}", html);
+    }
+
+    #[test]
+    fn test_render_mounted_page() {
+        let dir = std::env::temp_dir().join("template-render-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("about.html"), "About {{ query.name }}").unwrap();
+
+        let env = environment(dir.to_str().unwrap());
+        let html = render_page(
+            &env,
+            "about",
+            context! { query => BTreeMap::from([("name", "us")]) },
+        )
+        .unwrap();
+        assert_eq!(html, "About us");
+    }
+
+    #[test]
+    fn test_render_missing_page() {
+        let env = environment("/nonexistent");
+        let err = render_page(&env, "about", context! {}).unwrap_err();
+        assert_eq!(err.status(), 404);
+    }
+}
@@ -0,0 +1,131 @@
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageFormat, ImageReader, Limits};
+use knative_wasm_sdk::{http_handler, Error, Request, Response, Result, Router};
+
+#[http_handler]
+fn handle(req: Request) -> Result<Response> {
+    Router::new().post("/", thumbnail).handle(req)
+}
+
+const DEFAULT_SIZE: u32 = 128;
+const MAX_SIZE: u32 = 2048;
+/// Limit of the decoder's allocations, so a tiny compressed image can't
+/// claim gigabytes once decoded.
+const MAX_DECODE_ALLOC: u64 = 256 * 1024 * 1024;
+
+const PNG: &str = "image/png";
+const JPEG: &str = "image/jpeg";
+
+/**
+Decode the posted PNG or JPEG image, scale it down to fit "width" x "height"
+(keeping the aspect ratio), and encode it as PNG or JPEG depending on the
+`Accept` header. Decoding and resizing are deliberately done in guest memory,
+to exercise the memory, body size and fuel limits under realistic load.
+ */
+fn thumbnail(req: Request) -> Result<Response> {
+    let width = size(&req, "width")?;
+    let height = size(&req, "height")?;
+    let format = match req.preferred(&[PNG, JPEG]) {
+        Some(JPEG) => ImageFormat::Jpeg,
+        Some(_) => ImageFormat::Png,
+        None => {
+            return Err(Error::new(
+                406,
+                "only PNG and JPEG thumbnails are available",
+            ))
+        }
+    };
+
+    let img = decode(req.body())?.thumbnail(width, height);
+    let body = encode(img, format)?;
+    Ok(Response::ok()
+        .with_header("content-type", format.to_mime_type())
+        .with_body(body))
+}
+
+fn size(req: &Request, name: &str) -> Result<u32> {
+    match req.query(name) {
+        None => Ok(DEFAULT_SIZE),
+        Some(v) => match v.parse() {
+            Ok(n) if (1..=MAX_SIZE).contains(&n) => Ok(n),
+            _ => Err(Error::bad_request(format!(
+                "{} must be between 1 and {}, got {:?}",
+                name, MAX_SIZE, v
+            ))),
+        },
+    }
+}
+
+fn decode(bytes: &[u8]) -> Result<DynamicImage> {
+    let mut reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|err| Error::bad_request(format!("reading image: {}", err)))?;
+    let mut limits = Limits::default();
+    limits.max_alloc = Some(MAX_DECODE_ALLOC);
+    reader.limits(limits);
+    reader
+        .decode()
+        .map_err(|err| Error::bad_request(format!("decoding image: {}", err)))
+}
+
+fn encode(img: DynamicImage, format: ImageFormat) -> Result<Vec<u8>> {
+    // JPEG has no alpha channel.
+    let img = match format {
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(img.to_rgb8()),
+        _ => img,
+    };
+    let mut buf = Cursor::new(Vec::new());
+    img.write_to(&mut buf, format)
+        .map_err(|err| Error::internal(format!("encoding thumbnail: {}", err)))?;
+    Ok(buf.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, RgbaImage};
+    use knative_wasm_sdk::Method;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        encode(
+            DynamicImage::ImageRgba8(RgbaImage::new(width, height)),
+            ImageFormat::Png,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_thumbnail() {
+        let req = Request::new(Method::Post, "/?width=50&height=50")
+            .with_header("accept", "image/jpeg")
+            .with_body(png(200, 100));
+        let resp = thumbnail(req).unwrap();
+        assert_eq!(resp.header("content-type"), Some("image/jpeg"));
+
+        let img = image::load_from_memory(resp.body()).unwrap();
+        assert_eq!(img.dimensions(), (50, 25));
+    }
+
+    #[test]
+    fn test_thumbnail_errors() {
+        let status = |req: Request| thumbnail(req).unwrap_err().status();
+
+        assert_eq!(
+            status(Request::new(Method::Post, "/").with_body("not an image")),
+            400
+        );
+        assert_eq!(
+            status(Request::new(Method::Post, "/?width=0").with_body(png(1, 1))),
+            400
+        );
+        assert_eq!(
+            status(
+                Request::new(Method::Post, "/")
+                    .with_header("accept", "image/gif")
+                    .with_body(png(1, 1))
+            ),
+            406
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+
+use knative_wasm_sdk::{http_handler, Request, Response, Result, Router};
+use serde::Serialize;
+use wasi::cli::environment;
+use wasi::filesystem::preopens;
+
+#[http_handler]
+fn handle(req: Request) -> Result<Response> {
+    Router::new().get("/", echo).handle(req)
+}
+
+/// What the host configured for the guest.
+#[derive(Debug, PartialEq, Serialize)]
+struct WasiConfig {
+    args: Vec<String>,
+    env: BTreeMap<String, String>,
+    preopens: Vec<String>,
+}
+
+impl WasiConfig {
+    fn new(args: Vec<String>, env: Vec<(String, String)>, mut preopens: Vec<String>) -> Self {
+        preopens.sort();
+        WasiConfig {
+            args,
+            env: env.into_iter().collect(),
+            preopens,
+        }
+    }
+}
+
+/**
+Respond with the guest's arguments, environment variables and preopened
+directories, so tests can verify what the host wired through.
+ */
+fn echo(_: Request) -> Result<Response> {
+    let preopens = preopens::get_directories()
+        .into_iter()
+        .map(|(_, path)| path)
+        .collect();
+    let config = WasiConfig::new(
+        environment::get_arguments(),
+        environment::get_environment(),
+        preopens,
+    );
+    Response::json(&config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wasi_config_json() {
+        let config = WasiConfig::new(
+            vec!["module.wasm".to_string(), "--verbose".to_string()],
+            vec![
+                ("B".to_string(), "2".to_string()),
+                ("A".to_string(), "1".to_string()),
+            ],
+            vec!["/tmp".to_string(), "/data".to_string()],
+        );
+        let resp = Response::json(&config).unwrap();
+        assert_eq!(
+            std::str::from_utf8(resp.body()).unwrap(),
+            r#"{"args":["module.wasm","--verbose"],"env":{"A":"1","B":"2"},"preopens":["/data","/tmp"]}"#
+        );
+    }
+}
@@ -0,0 +1,161 @@
+/*!
+Attribute macros for the Knative WASM guest SDK. Use them through the
+re-exports in `knative_wasm_sdk` rather than depending on this crate
+directly.
+ */
+
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse2, Error, FnArg, ItemFn, ReturnType};
+
+/**
+Turn a `fn(Request) -> Result<Response>` into the component's
+`wasi:http/incoming-handler` export.
+
+The macro keeps the function as is and generates the exported type, the
+`wasi::http::incoming_handler::export!` call and the `Guest` impl that hands
+the request to `knative_wasm_sdk::serve`. The crate must depend on `wasi`
+directly, as the export macro refers to it by name.
+
+```ignore
+use knative_wasm_sdk::{http_handler, Request, Response, Result};
+
+#[http_handler]
+fn handle(req: Request) -> Result<Response> {
+    Ok(Response::text(format!("Hello, {}!", req.query("name").unwrap_or("WASI"))))
+}
+```
+ */
+#[proc_macro_attribute]
+pub fn http_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand(attr.into(), item.into())
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(attr: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    if !attr.is_empty() {
+        return Err(Error::new_spanned(attr, "http_handler takes no arguments"));
+    }
+    let func: ItemFn = parse2(item)?;
+    validate(&func)?;
+
+    let name = &func.sig.ident;
+    // Hygienic, so a handler named like a parameter isn't shadowed by it.
+    let request = Ident::new("__request", Span::mixed_site());
+    let response_out = Ident::new("__response_out", Span::mixed_site());
+    Ok(quote! {
+        #func
+
+        #[doc(hidden)]
+        struct __KnativeHttpHandler;
+
+        wasi::http::incoming_handler::export!(__KnativeHttpHandler);
+
+        impl exports::wasi::http::incoming_handler::Guest for __KnativeHttpHandler {
+            fn handle(
+                #request: wasi::http::types::IncomingRequest,
+                #response_out: wasi::http::types::ResponseOutparam,
+            ) {
+                ::knative_wasm_sdk::serve(#request, #response_out, #name);
+            }
+        }
+    })
+}
+
+fn validate(func: &ItemFn) -> syn::Result<()> {
+    let sig = &func.sig;
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(Error::new_spanned(
+            asyncness,
+            "http_handler cannot be async",
+        ));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &sig.generics,
+            "http_handler cannot be generic",
+        ));
+    }
+    if sig.inputs.len() != 1 || matches!(sig.inputs.first(), Some(FnArg::Receiver(_))) {
+        return Err(Error::new_spanned(
+            &sig.inputs,
+            "http_handler takes exactly one argument, the Request",
+        ));
+    }
+    if let ReturnType::Default = sig.output {
+        return Err(Error::new_spanned(
+            sig,
+            "http_handler must return Result<Response>",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_err(item: TokenStream2) -> String {
+        expand(TokenStream2::new(), item).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_expand() {
+        let out = expand(
+            TokenStream2::new(),
+            quote! { fn handle(req: Request) -> Result<Response> { todo!() } },
+        )
+        .unwrap()
+        .to_string();
+
+        assert!(out.starts_with("fn handle"));
+        assert!(out.contains("export ! (__KnativeHttpHandler)"));
+        assert!(out.contains("serve (__request , __response_out , handle)"));
+
+        for name in ["request", "response_out"] {
+            let ident = Ident::new(name, Span::call_site());
+            let out = expand(
+                TokenStream2::new(),
+                quote! { fn #ident(req: Request) -> Result<Response> { todo!() } },
+            )
+            .unwrap()
+            .to_string();
+            assert!(
+                out.contains(&format!("serve (__request , __response_out , {})", name)),
+                "{}",
+                out
+            );
+        }
+    }
+
+    #[test]
+    fn test_expand_rejects_invalid_signatures() {
+        assert_eq!(
+            expand(
+                quote! { foo },
+                quote! { fn handle(req: Request) -> Result<Response> {} }
+            )
+            .unwrap_err()
+            .to_string(),
+            "http_handler takes no arguments"
+        );
+        assert_eq!(
+            expand_err(quote! { async fn handle(req: Request) -> Result<Response> {} }),
+            "http_handler cannot be async"
+        );
+        assert_eq!(
+            expand_err(quote! { fn handle<T>(req: T) -> Result<Response> {} }),
+            "http_handler cannot be generic"
+        );
+        assert_eq!(
+            expand_err(quote! { fn handle(req: Request, n: u8) -> Result<Response> {} }),
+            "http_handler takes exactly one argument, the Request"
+        );
+        assert_eq!(
+            expand_err(quote! { fn handle(req: Request) {} }),
+            "http_handler must return Result<Response>"
+        );
+    }
+}
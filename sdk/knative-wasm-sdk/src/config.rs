@@ -0,0 +1,127 @@
+/*!
+Typed module configuration read from the environment.
+
+Environment variable names are matched case-insensitively against the
+struct's field names, so `MAX_ITEMS` fills `max_items`. Sequences are read
+as comma-separated values, and `Option` and `#[serde(default)]` fields may be
+left unset.
+
+```ignore
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Config {
+    greeting: String,
+    #[serde(default)]
+    max_items: usize,
+}
+
+#[http_handler]
+fn handle(req: Request) -> Result<Response> {
+    let config: Config = knative_wasm_sdk::config()?;
+    Ok(Response::text(config.greeting))
+}
+```
+ */
+
+use serde::de::DeserializeOwned;
+
+use crate::{Error, Result};
+
+/// Environment variable enabling dev mode, in which configuration errors
+/// are reported to the client in full.
+pub const DEV_MODE_ENV: &str = "KNATIVE_WASM_DEV";
+
+/**
+Deserialize the module's environment into `T`.
+
+An invalid configuration is a deployment problem, so it is reported as a 500
+error. Its details go to stderr, and are only included in the response in
+dev mode.
+ */
+pub fn config<T: DeserializeOwned>() -> Result<T> {
+    config_prefixed("")
+}
+
+/**
+Like [`config`], but only considers the variables starting with `prefix`,
+which is stripped before matching the field names.
+ */
+pub fn config_prefixed<T: DeserializeOwned>(prefix: &str) -> Result<T> {
+    from_vars(std::env::vars(), prefix, dev_mode())
+}
+
+/// Whether [`DEV_MODE_ENV`] is set to `true` or `1`.
+pub fn dev_mode() -> bool {
+    std::env::var(DEV_MODE_ENV).is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+}
+
+fn from_vars<T, I>(vars: I, prefix: &str, dev: bool) -> Result<T>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (String, String)>,
+{
+    envy::prefixed(prefix).from_iter(vars).map_err(|err| {
+        eprintln!("Invalid configuration: {}", err);
+        if dev {
+            Error::internal(format!("invalid configuration: {}", err))
+        } else {
+            Error::internal("invalid configuration")
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Config {
+        greeting: String,
+        #[serde(default)]
+        max_items: usize,
+        tags: Option<Vec<String>>,
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_from_vars() {
+        let env = vars(&[("GREETING", "hi"), ("MAX_ITEMS", "3"), ("TAGS", "a,b")]);
+        assert_eq!(
+            from_vars::<Config, _>(env, "", false).unwrap(),
+            Config {
+                greeting: "hi".to_string(),
+                max_items: 3,
+                tags: Some(vec!["a".to_string(), "b".to_string()]),
+            }
+        );
+
+        let env = vars(&[("APP_GREETING", "hi"), ("GREETING", "ignored")]);
+        assert_eq!(
+            from_vars::<Config, _>(env, "APP_", false).unwrap(),
+            Config {
+                greeting: "hi".to_string(),
+                max_items: 0,
+                tags: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_vars_errors() {
+        let err = from_vars::<Config, _>(vars(&[]), "", false).unwrap_err();
+        assert_eq!(err, Error::internal("invalid configuration"));
+
+        let env = vars(&[("GREETING", "hi"), ("MAX_ITEMS", "many")]);
+        let err = from_vars::<Config, _>(env, "", true).unwrap_err();
+        assert_eq!(err.status(), 500);
+        assert!(err.message().contains("MAX_ITEMS"), "{}", err.message());
+    }
+}
@@ -0,0 +1,114 @@
+use std::fmt;
+
+/// Result type returned by handlers.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/**
+An error carrying the HTTP status it should be reported with.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    status: u16,
+    message: String,
+    headers: Vec<(String, String)>,
+}
+
+impl Error {
+    pub fn new(status: u16, message: impl Into<String>) -> Self {
+        Error {
+            status,
+            message: message.into(),
+            headers: Vec::new(),
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Error::new(400, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Error::new(404, message)
+    }
+
+    pub fn method_not_allowed(message: impl Into<String>) -> Self {
+        Error::new(405, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Error::new(500, message)
+    }
+
+    /// Add a header to the response the error is converted to, replacing
+    /// any previous value.
+    pub fn with_header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.retain(|(k, _)| !k.eq_ignore_ascii_case(name));
+        self.headers.push((name.to_ascii_lowercase(), value.into()));
+        self
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /**
+    Convert the error into a JSON response of the form `{"error": "..."}`.
+     */
+    pub fn into_response(self) -> crate::Response {
+        let body = serde_json::json!({ "error": self.message });
+        let resp = crate::Response::new(self.status)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string());
+        self.headers
+            .into_iter()
+            .fold(resp, |resp, (k, v)| resp.with_header(&k, v))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.status, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::bad_request(format!("invalid JSON: {}", err))
+    }
+}
+
+impl From<std::str::Utf8Error> for Error {
+    fn from(err: std::str::Utf8Error) -> Self {
+        Error::bad_request(format!("invalid UTF-8: {}", err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_response() {
+        let resp = Error::not_found("no \"such\" route").into_response();
+        assert_eq!(resp.status(), 404);
+        assert_eq!(resp.header("Content-Type"), Some("application/json"));
+        assert_eq!(resp.body(), br#"{"error":"no \"such\" route"}"#);
+
+        let resp = Error::method_not_allowed("nope")
+            .with_header("Allow", "GET")
+            .into_response();
+        assert_eq!(resp.header("allow"), Some("GET"));
+    }
+}
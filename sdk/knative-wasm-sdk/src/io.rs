@@ -0,0 +1,133 @@
+//! Blocking helpers for WASI streams and outgoing HTTP requests.
+
+use url::Url;
+use wasi::http::types::{
+    Fields, FutureIncomingResponse, FutureTrailers, IncomingResponse, OutgoingRequest, Scheme,
+    Trailers,
+};
+use wasi::io::streams::{InputStream, OutputStream, StreamError};
+
+use crate::{Error, Result};
+
+/// The most `blocking_write_and_flush` accepts in a single call.
+const CHUNK_SIZE: usize = 4096;
+
+/// Read the stream until it is closed.
+pub fn read_all(stream: &InputStream) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    loop {
+        match stream.blocking_read(CHUNK_SIZE as u64) {
+            Ok(chunk) => buf.extend_from_slice(&chunk),
+            Err(StreamError::Closed) => return Ok(buf),
+            Err(StreamError::LastOperationFailed(err)) => {
+                return Err(Error::internal(format!(
+                    "reading body: {}",
+                    err.to_debug_string()
+                )))
+            }
+        }
+    }
+}
+
+/// Write all bytes to the stream, splitting them into chunks the host accepts.
+pub fn write_all(stream: &OutputStream, bytes: &[u8]) -> Result<()> {
+    for chunk in bytes.chunks(CHUNK_SIZE) {
+        stream
+            .blocking_write_and_flush(chunk)
+            .map_err(|err| Error::internal(format!("writing body: {:?}", err)))?;
+    }
+    Ok(())
+}
+
+/// Copy everything from the input stream to the output stream without
+/// buffering it in guest memory.
+pub fn copy(from: &InputStream, to: &OutputStream) -> Result<()> {
+    loop {
+        match to.blocking_splice(from, CHUNK_SIZE as u64) {
+            Ok(_) => {}
+            Err(StreamError::Closed) => return Ok(()),
+            Err(StreamError::LastOperationFailed(err)) => {
+                return Err(Error::internal(format!(
+                    "copying stream: {}",
+                    err.to_debug_string()
+                )))
+            }
+        }
+    }
+}
+
+/**
+Create an outgoing request to the URL's scheme, authority, path and query,
+with the given headers. The method defaults to GET.
+ */
+pub fn outgoing_request(url: &Url, headers: Fields) -> Result<OutgoingRequest> {
+    let scheme = match url.scheme() {
+        "http" => Scheme::Http,
+        "https" => Scheme::Https,
+        other => {
+            return Err(Error::bad_request(format!(
+                "unsupported scheme {:?}",
+                other
+            )))
+        }
+    };
+    let host = url
+        .host_str()
+        .ok_or_else(|| Error::bad_request(format!("url {} has no host", url)))?;
+    let authority = match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    };
+
+    let req = OutgoingRequest::new(headers);
+    req.set_scheme(Some(&scheme))
+        .and_then(|_| req.set_authority(Some(&authority)))
+        .and_then(|_| req.set_path_with_query(Some(path_with_query(url))))
+        .map_err(|_| Error::bad_request(format!("invalid url {}", url)))?;
+    Ok(req)
+}
+
+fn path_with_query(url: &Url) -> &str {
+    &url[url::Position::BeforePath..url::Position::AfterQuery]
+}
+
+/// Block until the response to an outgoing request arrives. Transport
+/// errors are reported as 502.
+pub fn wait_response(future: &FutureIncomingResponse) -> Result<IncomingResponse> {
+    let result = loop {
+        match future.get() {
+            Some(result) => break result,
+            None => future.subscribe().block(),
+        }
+    };
+    result
+        .map_err(|_| Error::internal("upstream response already taken"))?
+        .map_err(|code| Error::new(502, format!("upstream error: {:?}", code)))
+}
+
+/// Block until the trailers following a finished incoming body arrive.
+/// Transport errors are reported as 502.
+pub fn wait_trailers(future: &FutureTrailers) -> Result<Option<Trailers>> {
+    let result = loop {
+        match future.get() {
+            Some(result) => break result,
+            None => future.subscribe().block(),
+        }
+    };
+    result
+        .map_err(|_| Error::internal("trailers already taken"))?
+        .map_err(|code| Error::new(502, format!("reading trailers: {:?}", code)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_with_query() {
+        let url = Url::parse("https://example.com:8443/a/b?c=d#frag").unwrap();
+        assert_eq!(path_with_query(&url), "/a/b?c=d");
+        let url = Url::parse("http://example.com").unwrap();
+        assert_eq!(path_with_query(&url), "/");
+    }
+}
@@ -0,0 +1,60 @@
+/*!
+Helpers for writing [wasi-http](https://github.com/WebAssembly/wasi-http)
+guest modules served by Knative WASM.
+
+The SDK converts the WASI resource types into plain [`Request`] and
+[`Response`] values, routes them with a [`Router`], and turns any
+[`Error`] returned by a handler into a proper HTTP response.
+
+```ignore
+use knative_wasm_sdk::{http_handler, Request, Response, Result, Router};
+
+#[http_handler]
+fn handle(req: Request) -> Result<Response> {
+    Router::new().get("/hello/:name", hello).handle(req)
+}
+
+fn hello(req: Request) -> Result<Response> {
+    Ok(Response::text(format!("Hello, {}!", req.param("name").unwrap_or("WASI"))))
+}
+```
+
+The [`http_handler`] attribute generates the `wasi:http/incoming-handler`
+export. Modules that need the WASI resources themselves can implement the
+`Guest` trait by hand and call [`serve`] instead.
+ */
+
+pub mod config;
+mod error;
+pub mod io;
+mod request;
+mod response;
+mod router;
+
+pub use config::config;
+pub use error::{Error, Result};
+pub use knative_wasm_sdk_macros::http_handler;
+pub use request::{Method, Request};
+pub use response::Response;
+pub use router::Router;
+
+pub use wasi;
+
+use wasi::http::types::{IncomingRequest, ResponseOutparam};
+
+/**
+Read the incoming request, pass it to the handler, and write the handler's
+response (or its error converted to a response) back to the host.
+ */
+pub fn serve<F>(request: IncomingRequest, response_out: ResponseOutparam, handler: F)
+where
+    F: FnOnce(Request) -> Result<Response>,
+{
+    let response = Request::from_incoming(request)
+        .and_then(handler)
+        .unwrap_or_else(Error::into_response);
+
+    if let Err(err) = response.send(response_out) {
+        eprintln!("Failed to send the response: {}", err);
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use wasi::http::types::{IncomingBody, IncomingRequest};
+
+use crate::{io, Error, Result};
+
+/**
+HTTP method of a request.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Method {
+    Get,
+    Head,
+    Post,
+    Put,
+    Delete,
+    Connect,
+    Options,
+    Trace,
+    Patch,
+    Other(String),
+}
+
+impl Method {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Method::Get => "GET",
+            Method::Head => "HEAD",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+            Method::Connect => "CONNECT",
+            Method::Options => "OPTIONS",
+            Method::Trace => "TRACE",
+            Method::Patch => "PATCH",
+            Method::Other(m) => m,
+        }
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<wasi::http::types::Method> for Method {
+    fn from(m: wasi::http::types::Method) -> Self {
+        use wasi::http::types::Method as M;
+        match m {
+            M::Get => Method::Get,
+            M::Head => Method::Head,
+            M::Post => Method::Post,
+            M::Put => Method::Put,
+            M::Delete => Method::Delete,
+            M::Connect => Method::Connect,
+            M::Options => Method::Options,
+            M::Trace => Method::Trace,
+            M::Patch => Method::Patch,
+            M::Other(m) => Method::Other(m),
+        }
+    }
+}
+
+/**
+A fully read incoming request.
+ */
+#[derive(Debug, Clone)]
+pub struct Request {
+    method: Method,
+    path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, Vec<u8>)>,
+    body: Vec<u8>,
+    params: HashMap<String, String>,
+}
+
+impl Request {
+    /**
+    Create a request without headers or body, mostly useful in tests.
+     */
+    pub fn new(method: Method, path_with_query: &str) -> Self {
+        let (path, query) = match path_with_query.split_once('?') {
+            Some((path, query)) => (path, parse_query(query)),
+            None => (path_with_query, Vec::new()),
+        };
+        let path = if path.is_empty() { "/" } else { path };
+        Request {
+            method,
+            path: path.to_string(),
+            query,
+            headers: Vec::new(),
+            body: Vec::new(),
+            params: HashMap::new(),
+        }
+    }
+
+    /**
+    Read the WASI request, including its whole body.
+     */
+    pub fn from_incoming(incoming: IncomingRequest) -> Result<Self> {
+        let pq = incoming.path_with_query().unwrap_or_default();
+        let mut req = Request::new(incoming.method().into(), &pq);
+        req.headers = incoming.headers().entries();
+
+        let body = incoming
+            .consume()
+            .map_err(|_| Error::internal("request body already consumed"))?;
+        let stream = body
+            .stream()
+            .map_err(|_| Error::internal("request body stream already taken"))?;
+        req.body = io::read_all(&stream)?;
+        drop(stream);
+        IncomingBody::finish(body);
+
+        Ok(req)
+    }
+
+    pub fn with_header(mut self, name: &str, value: impl Into<Vec<u8>>) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+
+    pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /**
+    Get the first value of a decoded query parameter.
+     */
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn query_pairs(&self) -> &[(String, String)] {
+        &self.query
+    }
+
+    /**
+    Get the first value of a header, matching the name case-insensitively.
+    Values that aren't valid UTF-8 are skipped.
+     */
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .filter(|(k, _)| k.eq_ignore_ascii_case(name))
+            .find_map(|(_, v)| std::str::from_utf8(v).ok())
+    }
+
+    pub fn headers(&self) -> &[(String, Vec<u8>)] {
+        &self.headers
+    }
+
+    /**
+    Get the media type of the body, without parameters such as `charset`.
+     */
+    pub fn content_type(&self) -> Option<&str> {
+        self.header("content-type")
+            .map(|ct| ct.split(';').next().unwrap_or_default().trim())
+    }
+
+    /**
+    Pick the media type the client prefers among the offered ones, based on
+    the `Accept` header. Without the header the first offered type is
+    returned; `None` means the client accepts none of them.
+     */
+    pub fn preferred<'a>(&self, offered: &[&'a str]) -> Option<&'a str> {
+        let Some(accept) = self.header("accept") else {
+            return offered.first().copied();
+        };
+        let ranges: Vec<(&str, f32)> = accept.split(',').filter_map(media_range).collect();
+        let mut best: Option<(&'a str, f32)> = None;
+        for &candidate in offered {
+            let q = quality(&ranges, candidate);
+            if q > 0.0 && best.is_none_or(|(_, bq)| q > bq) {
+                best = Some((candidate, q));
+            }
+        }
+        best.map(|(candidate, _)| candidate)
+    }
+
+    /**
+    Get a path parameter captured by the [`Router`](crate::Router).
+     */
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
+
+    pub(crate) fn set_params(&mut self, params: HashMap<String, String>) {
+        self.params = params;
+    }
+
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    pub fn text(&self) -> Result<&str> {
+        Ok(std::str::from_utf8(&self.body)?)
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+fn media_range(range: &str) -> Option<(&str, f32)> {
+    let mut parts = range.split(';').map(str::trim);
+    let media = parts.next().filter(|m| !m.is_empty())?;
+    let q = parts
+        .filter_map(|p| p.strip_prefix("q="))
+        .find_map(|q| q.parse().ok())
+        .unwrap_or(1.0);
+    Some((media, q))
+}
+
+/// Quality of the most specific range matching the media type.
+fn quality(ranges: &[(&str, f32)], media: &str) -> f32 {
+    let (ty, _) = media.split_once('/').unwrap_or((media, ""));
+    let specificity = |range: &str| {
+        if range.eq_ignore_ascii_case(media) {
+            Some(2)
+        } else if range
+            .strip_suffix("/*")
+            .is_some_and(|t| t.eq_ignore_ascii_case(ty))
+        {
+            Some(1)
+        } else if range == "*/*" {
+            Some(0)
+        } else {
+            None
+        }
+    };
+    ranges
+        .iter()
+        .filter_map(|(range, q)| specificity(range).map(|s| (s, *q)))
+        .max_by_key(|(s, _)| *s)
+        .map_or(0.0, |(_, q)| q)
+}
+
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(k), decode(v))
+        })
+        .collect()
+}
+
+fn decode(s: &str) -> String {
+    let s = s.replace('+', " ");
+    urlencoding::decode(&s).map(|d| d.into_owned()).unwrap_or(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query() {
+        let req = Request::new(
+            Method::Get,
+            "/search?q=Happy%20testing&lang=en+US&empty&q=again",
+        );
+        assert_eq!(req.path(), "/search");
+        assert_eq!(req.query("q"), Some("Happy testing"));
+        assert_eq!(req.query("lang"), Some("en US"));
+        assert_eq!(req.query("empty"), Some(""));
+        assert_eq!(req.query("missing"), None);
+
+        let req = Request::new(Method::Get, "?");
+        assert_eq!(req.path(), "/");
+        assert!(req.query_pairs().is_empty());
+    }
+
+    #[test]
+    fn test_header() {
+        let req = Request::new(Method::Get, "/").with_header("Content-Type", "text/plain");
+        assert_eq!(req.header("content-type"), Some("text/plain"));
+        assert_eq!(req.header("accept"), None);
+    }
+
+    #[test]
+    fn test_content_type() {
+        let req = Request::new(Method::Post, "/")
+            .with_header("content-type", "application/json; charset=utf-8");
+        assert_eq!(req.content_type(), Some("application/json"));
+        assert_eq!(Request::new(Method::Post, "/").content_type(), None);
+    }
+
+    #[test]
+    fn test_preferred() {
+        let offered = ["text/plain", "application/json"];
+        let preferred = |accept: &str| {
+            Request::new(Method::Get, "/")
+                .with_header("accept", accept)
+                .preferred(&offered)
+        };
+
+        assert_eq!(
+            Request::new(Method::Get, "/").preferred(&offered),
+            Some("text/plain")
+        );
+        assert_eq!(preferred("*/*"), Some("text/plain"));
+        assert_eq!(preferred("application/json"), Some("application/json"));
+        assert_eq!(
+            preferred("text/*;q=0.5, application/json"),
+            Some("application/json")
+        );
+        assert_eq!(
+            preferred("application/json;q=0.1, */*;q=0.5"),
+            Some("text/plain")
+        );
+        assert_eq!(preferred("text/html, text/plain;q=0"), None);
+    }
+
+    #[test]
+    fn test_json() {
+        let req = Request::new(Method::Post, "/").with_body(r#"{"a": 1}"#);
+        let v: serde_json::Value = req.json().unwrap();
+        assert_eq!(v["a"], 1);
+
+        let err = req.with_body("{").json::<serde_json::Value>().unwrap_err();
+        assert_eq!(err.status(), 400);
+    }
+}
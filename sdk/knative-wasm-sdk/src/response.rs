@@ -0,0 +1,253 @@
+use std::fmt;
+
+use serde::Serialize;
+use wasi::http::types::{ErrorCode, Fields, OutgoingBody, OutgoingResponse, ResponseOutparam};
+use wasi::io::streams::OutputStream;
+
+use crate::{io, Error, Result};
+
+/**
+A response built by a handler and sent back to the host by [`serve`](crate::serve).
+ */
+pub struct Response {
+    status: u16,
+    headers: Vec<(String, Vec<u8>)>,
+    body: Vec<u8>,
+    writer: Option<BodyWriter>,
+}
+
+type BodyWriter = Box<dyn FnOnce(&OutputStream) -> Result<()>>;
+
+impl Response {
+    pub fn new(status: u16) -> Self {
+        Response {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+            writer: None,
+        }
+    }
+
+    pub fn ok() -> Self {
+        Response::new(200)
+    }
+
+    /**
+    A `200 OK` response with a `text/plain` body.
+     */
+    pub fn text(body: impl Into<String>) -> Self {
+        Response::ok()
+            .with_header("content-type", "text/plain; charset=utf-8")
+            .with_body(body.into())
+    }
+
+    /**
+    A `200 OK` response with the value serialized as JSON.
+     */
+    pub fn json<T: Serialize + ?Sized>(value: &T) -> Result<Self> {
+        let body = serde_json::to_vec(value)
+            .map_err(|err| Error::internal(format!("serializing response: {}", err)))?;
+        Ok(Response::ok()
+            .with_header("content-type", "application/json")
+            .with_body(body))
+    }
+
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    /**
+    Set a header, replacing any previous values of the same name.
+     */
+    pub fn with_header(mut self, name: &str, value: impl Into<Vec<u8>>) -> Self {
+        self.headers.retain(|(k, _)| !k.eq_ignore_ascii_case(name));
+        self.headers.push((name.to_ascii_lowercase(), value.into()));
+        self
+    }
+
+    pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /**
+    Stream the rest of the body with the given function, once the headers
+    and any buffered body were sent. Useful for long-lived responses, like
+    server-sent events, where the body is produced over time.
+     */
+    pub fn with_writer<F>(mut self, writer: F) -> Self
+    where
+        F: FnOnce(&OutputStream) -> Result<()> + 'static,
+    {
+        self.writer = Some(Box::new(writer));
+        self
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .and_then(|(_, v)| std::str::from_utf8(v).ok())
+    }
+
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /**
+    Send the response to the host. Handlers using [`serve`](crate::serve)
+    never need to call this directly.
+
+    A response the host would refuse, like one with a forbidden header, is
+    replaced with a 500 error response, and the error is returned.
+     */
+    pub fn send(self, response_out: ResponseOutparam) -> Result<()> {
+        match self.outgoing() {
+            Ok(resp) => self.write(resp, response_out),
+            Err(err) => {
+                let fallback = err.clone().into_response();
+                match fallback.outgoing() {
+                    Ok(resp) => fallback.write(resp, response_out)?,
+                    Err(_) => ResponseOutparam::set(
+                        response_out,
+                        Err(ErrorCode::InternalError(Some(err.message().to_string()))),
+                    ),
+                }
+                Err(err)
+            }
+        }
+    }
+
+    fn outgoing(&self) -> Result<OutgoingResponse> {
+        self.validate()?;
+        let headers = Fields::from_list(&self.headers)
+            .map_err(|err| Error::internal(format!("invalid headers: {:?}", err)))?;
+        let resp = OutgoingResponse::new(headers);
+        resp.set_status_code(self.status)
+            .map_err(|_| Error::internal(format!("invalid status: {}", self.status)))?;
+        Ok(resp)
+    }
+
+    fn write(self, resp: OutgoingResponse, response_out: ResponseOutparam) -> Result<()> {
+        let body = resp.body().unwrap();
+
+        ResponseOutparam::set(response_out, Ok(resp));
+
+        let out = body.write().unwrap();
+        io::write_all(&out, &self.body)?;
+        if let Some(writer) = self.writer {
+            writer(&out)?;
+        }
+        drop(out);
+
+        OutgoingBody::finish(body, None)
+            .map_err(|err| Error::internal(format!("finishing body: {:?}", err)))
+    }
+
+    /// Check the status and headers against the rules wasi-http hosts
+    /// enforce, so a bad response is caught before it reaches the host.
+    fn validate(&self) -> Result<()> {
+        if !(100..=599).contains(&self.status) {
+            return Err(Error::internal(format!("invalid status: {}", self.status)));
+        }
+        for (name, value) in &self.headers {
+            if name.is_empty() || !name.bytes().all(is_token_byte) {
+                return Err(Error::internal(format!("invalid header name {:?}", name)));
+            }
+            if FORBIDDEN_HEADERS.contains(&name.as_str()) {
+                return Err(Error::internal(format!("forbidden header {:?}", name)));
+            }
+            if value.iter().any(|b| matches!(b, b'\r' | b'\n' | 0)) {
+                return Err(Error::internal(format!(
+                    "invalid value for header {:?}",
+                    name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Headers wasi-http hosts don't let guests set, in lower case.
+const FORBIDDEN_HEADERS: [&str; 10] = [
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "proxy-connection",
+    "te",
+    "transfer-encoding",
+    "upgrade",
+    "host",
+    "http2-settings",
+];
+
+fn is_token_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+impl fmt::Debug for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .field("body", &self.body)
+            .field("streaming", &self.writer.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(Response::text("ok").validate(), Ok(()));
+        assert_eq!(
+            Response::ok().with_header("Connection", "close").validate(),
+            Err(Error::internal("forbidden header \"connection\""))
+        );
+        assert_eq!(
+            Response::ok().with_header("x-a", "a\r\nb: c").validate(),
+            Err(Error::internal("invalid value for header \"x-a\""))
+        );
+        assert_eq!(
+            Response::ok().with_header("x a", "b").validate(),
+            Err(Error::internal("invalid header name \"x a\""))
+        );
+        assert_eq!(
+            Response::new(1000).validate(),
+            Err(Error::internal("invalid status: 1000"))
+        );
+        assert_eq!(Error::internal("x").into_response().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_text() {
+        let resp = Response::text("hi").with_status(201);
+        assert_eq!(resp.status(), 201);
+        assert_eq!(resp.header("content-type"), Some("text/plain; charset=utf-8"));
+        assert_eq!(resp.body(), b"hi");
+    }
+
+    #[test]
+    fn test_json() {
+        let resp = Response::json(&serde_json::json!({"text": "a\"b"})).unwrap();
+        assert_eq!(resp.header("Content-Type"), Some("application/json"));
+        assert_eq!(resp.body(), br#"{"text":"a\"b"}"#);
+    }
+
+    #[test]
+    fn test_with_header_replaces() {
+        let resp = Response::ok()
+            .with_header("X-Test", "1")
+            .with_header("x-test", "2");
+        assert_eq!(resp.header("x-test"), Some("2"));
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use crate::{Error, Method, Request, Response, Result};
+
+type Handler = Box<dyn Fn(Request) -> Result<Response>>;
+
+struct Route {
+    method: Method,
+    segments: Vec<String>,
+    handler: Handler,
+}
+
+/**
+Dispatches requests to handlers by method and path.
+
+Path patterns are split on `/`; a segment starting with `:` captures the
+matching request segment as a percent-decoded parameter, available via
+[`Request::param`], and a trailing `*` matches any remainder.
+ */
+#[derive(Default)]
+pub struct Router {
+    routes: Vec<Route>,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Router::default()
+    }
+
+    pub fn route<H>(mut self, method: Method, pattern: &str, handler: H) -> Self
+    where
+        H: Fn(Request) -> Result<Response> + 'static,
+    {
+        self.routes.push(Route {
+            method,
+            segments: split(pattern).map(String::from).collect(),
+            handler: Box::new(handler),
+        });
+        self
+    }
+
+    pub fn get<H>(self, pattern: &str, handler: H) -> Self
+    where
+        H: Fn(Request) -> Result<Response> + 'static,
+    {
+        self.route(Method::Get, pattern, handler)
+    }
+
+    pub fn post<H>(self, pattern: &str, handler: H) -> Self
+    where
+        H: Fn(Request) -> Result<Response> + 'static,
+    {
+        self.route(Method::Post, pattern, handler)
+    }
+
+    pub fn put<H>(self, pattern: &str, handler: H) -> Self
+    where
+        H: Fn(Request) -> Result<Response> + 'static,
+    {
+        self.route(Method::Put, pattern, handler)
+    }
+
+    pub fn delete<H>(self, pattern: &str, handler: H) -> Self
+    where
+        H: Fn(Request) -> Result<Response> + 'static,
+    {
+        self.route(Method::Delete, pattern, handler)
+    }
+
+    /**
+    Run the first route matching the request. Returns a 404 error when no
+    pattern matches the path, and a 405 error when the path matches only
+    for other methods, listing those in its `Allow` header.
+     */
+    pub fn handle(&self, mut req: Request) -> Result<Response> {
+        let mut allowed: Vec<&Method> = Vec::new();
+        for route in &self.routes {
+            let Some(params) = match_path(&route.segments, req.path()) else {
+                continue;
+            };
+            allowed.push(&route.method);
+            if route.method == Method::Get {
+                allowed.push(&Method::Head);
+            }
+            if route.method == *req.method()
+                || (route.method == Method::Get && *req.method() == Method::Head)
+            {
+                req.set_params(params);
+                return (route.handler)(req);
+            }
+        }
+        if !allowed.is_empty() {
+            let mut allow: Vec<&str> = Vec::new();
+            for method in allowed.iter().map(|m| m.as_str()) {
+                if !allow.contains(&method) {
+                    allow.push(method);
+                }
+            }
+            Err(Error::method_not_allowed(format!(
+                "method {} not allowed for {}",
+                req.method(),
+                req.path()
+            ))
+            .with_header("allow", allow.join(", ")))
+        } else {
+            Err(Error::not_found(format!("no route for {}", req.path())))
+        }
+    }
+}
+
+fn split(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|s| !s.is_empty())
+}
+
+fn match_path(pattern: &[String], path: &str) -> Option<HashMap<String, String>> {
+    let mut params = HashMap::new();
+    let mut segments = split(path);
+    for p in pattern {
+        if p == "*" {
+            return Some(params);
+        }
+        let s = segments.next()?;
+        if let Some(name) = p.strip_prefix(':') {
+            let value = urlencoding::decode(s).map_or_else(|_| s.to_string(), |v| v.into_owned());
+            params.insert(name.to_string(), value);
+        } else if p != s {
+            return None;
+        }
+    }
+    segments.next().is_none().then_some(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn router() -> Router {
+        Router::new()
+            .get("/", |_| Ok(Response::text("root")))
+            .get("/items/:id", |req| {
+                Ok(Response::text(format!("item {}", req.param("id").unwrap())))
+            })
+            .post("/items", |_| Ok(Response::new(201)))
+            .get("/static/*", |req| Ok(Response::text(req.path().to_string())))
+    }
+
+    #[test]
+    fn test_handle() {
+        let r = router();
+        let body = |m, p| r.handle(Request::new(m, p)).map(|resp| resp.body().to_vec());
+
+        assert_eq!(body(Method::Get, "/"), Ok(b"root".to_vec()));
+        assert_eq!(body(Method::Get, "/items/42"), Ok(b"item 42".to_vec()));
+        assert_eq!(body(Method::Head, "/items/42/"), Ok(b"item 42".to_vec()));
+        assert_eq!(
+            body(Method::Get, "/items/J%C3%BCrgen%20X"),
+            Ok("item Jürgen X".as_bytes().to_vec())
+        );
+        assert_eq!(body(Method::Get, "/items/%FF"), Ok(b"item %FF".to_vec()));
+        assert_eq!(body(Method::Get, "/static/a/b.css"), Ok(b"/static/a/b.css".to_vec()));
+        assert_eq!(
+            r.handle(Request::new(Method::Post, "/items")).unwrap().status(),
+            201
+        );
+    }
+
+    #[test]
+    fn test_handle_errors() {
+        let r = router();
+        let status = |m, p| r.handle(Request::new(m, p)).unwrap_err().status();
+
+        assert_eq!(status(Method::Get, "/missing"), 404);
+        assert_eq!(status(Method::Get, "/items/1/2"), 404);
+        assert_eq!(status(Method::Delete, "/items/1"), 405);
+
+        let err = r
+            .handle(Request::new(Method::Delete, "/items"))
+            .unwrap_err();
+        assert_eq!(err.header("Allow"), Some("POST"));
+        let err = r.handle(Request::new(Method::Put, "/items/1")).unwrap_err();
+        assert_eq!(err.header("Allow"), Some("GET, HEAD"));
+    }
+}
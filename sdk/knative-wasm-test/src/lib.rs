@@ -0,0 +1,211 @@
+/*!
+In-process test harness for [wasi-http](https://github.com/WebAssembly/wasi-http)
+guest modules.
+
+The harness loads a built component into a Wasmtime engine linked with the
+WASI CLI, filesystem and `wasi:http` imports, and hands each request to a
+fresh instance, so module authors can assert on responses without deploying
+anything.
+
+```ignore
+use knative_wasm_test::TestHarness;
+
+#[tokio::test]
+async fn reverses_text() -> anyhow::Result<()> {
+    let harness = TestHarness::from_file("target/wasm32-wasip1/release/reverse_text.wasm")?;
+    let resp = harness.get("/?text=abc").await?;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.text()?, "cba");
+    Ok(())
+}
+```
+ */
+
+mod response;
+
+pub use response::TestResponse;
+
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use bytes::Bytes;
+use http_body_util::{combinators::BoxBody, BodyExt, Full};
+use wasmtime::component::{Component, Linker, ResourceTable};
+use wasmtime::{Config, Engine, Store};
+use wasmtime_wasi::{IoView, WasiCtx, WasiCtxBuilder, WasiView};
+use wasmtime_wasi_http::bindings::http::types::Scheme;
+use wasmtime_wasi_http::bindings::ProxyPre;
+use wasmtime_wasi_http::{WasiHttpCtx, WasiHttpView};
+
+/**
+A compiled component ready to serve test requests.
+ */
+pub struct TestHarness {
+    engine: Engine,
+    pre: ProxyPre<ClientState>,
+    env: Vec<(String, String)>,
+    args: Vec<String>,
+}
+
+impl TestHarness {
+    /**
+    Compile the component at the given path and link it with the WASI
+    imports. Besides `wasi:http/proxy`, this covers the `wasi:cli` and
+    `wasi:filesystem` interfaces that modules reading their environment, and
+    components built with the default WASI adapter, import.
+     */
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let mut config = Config::new();
+        config.async_support(true);
+        let engine = Engine::new(&config)?;
+
+        let component = Component::from_file(&engine, path)
+            .with_context(|| format!("loading component {}", path.display()))?;
+        let mut linker = Linker::new(&engine);
+        wasmtime_wasi::add_to_linker_async(&mut linker)?;
+        wasmtime_wasi_http::add_only_http_to_linker_async(&mut linker)?;
+        let pre = ProxyPre::new(linker.instantiate_pre(&component)?)?;
+
+        Ok(TestHarness {
+            engine,
+            pre,
+            env: Vec::new(),
+            args: Vec::new(),
+        })
+    }
+
+    /**
+    Set an environment variable visible to the guest.
+     */
+    pub fn with_env(mut self, key: &str, value: &str) -> Self {
+        self.env.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /**
+    Append a command-line argument visible to the guest.
+     */
+    pub fn with_arg(mut self, arg: &str) -> Self {
+        self.args.push(arg.to_string());
+        self
+    }
+
+    pub async fn get(&self, path_with_query: &str) -> anyhow::Result<TestResponse> {
+        self.send(request("GET", path_with_query).body(Bytes::new())?)
+            .await
+    }
+
+    pub async fn post(
+        &self,
+        path_with_query: &str,
+        body: impl Into<Bytes>,
+    ) -> anyhow::Result<TestResponse> {
+        self.send(request("POST", path_with_query).body(body.into())?)
+            .await
+    }
+
+    /**
+    Send the request to a fresh instance of the component and collect the
+    whole response.
+     */
+    pub async fn send(&self, req: hyper::Request<Bytes>) -> anyhow::Result<TestResponse> {
+        let mut store = Store::new(&self.engine, self.client_state());
+
+        let (parts, body) = req.into_parts();
+        let body: BoxBody<Bytes, hyper::Error> =
+            Full::new(body).map_err(|never| match never {}).boxed();
+        let req = store
+            .data_mut()
+            .new_incoming_request(Scheme::Http, hyper::Request::from_parts(parts, body))?;
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        let out = store.data_mut().new_response_outparam(sender)?;
+
+        let proxy = self.pre.instantiate_async(&mut store).await?;
+        let task = tokio::task::spawn(async move {
+            proxy
+                .wasi_http_incoming_handler()
+                .call_handle(&mut store, req, out)
+                .await
+        });
+
+        match receiver.await {
+            Ok(Ok(resp)) => {
+                let (parts, body) = resp.into_parts();
+                let body = body.collect().await?.to_bytes();
+                task.await??;
+                Ok(TestResponse::new(parts.status.as_u16(), parts.headers, body))
+            }
+            Ok(Err(code)) => Err(anyhow!("guest returned an error: {:?}", code)),
+            // The guest never set the response; surface why.
+            Err(_) => match task.await? {
+                Ok(()) => Err(anyhow!("guest did not set a response")),
+                Err(err) => Err(err.context("guest trapped before responding")),
+            },
+        }
+    }
+
+    fn client_state(&self) -> ClientState {
+        let mut builder = WasiCtxBuilder::new();
+        builder
+            .inherit_stdio()
+            .envs(&self.env[..])
+            .args(&self.args[..]);
+        ClientState {
+            wasi: builder.build(),
+            http: WasiHttpCtx::new(),
+            table: ResourceTable::new(),
+        }
+    }
+}
+
+fn request(method: &str, path_with_query: &str) -> hyper::http::request::Builder {
+    hyper::Request::builder()
+        .method(method)
+        .uri(format!("http://localhost{}", path_with_query))
+}
+
+struct ClientState {
+    wasi: WasiCtx,
+    http: WasiHttpCtx,
+    table: ResourceTable,
+}
+
+impl IoView for ClientState {
+    fn table(&mut self) -> &mut ResourceTable {
+        &mut self.table
+    }
+}
+
+impl WasiView for ClientState {
+    fn ctx(&mut self) -> &mut WasiCtx {
+        &mut self.wasi
+    }
+}
+
+impl WasiHttpView for ClientState {
+    fn ctx(&mut self) -> &mut WasiHttpCtx {
+        &mut self.http
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Responds with 200 plus the number of environment variables it sees.
+    const ENV_STATUS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/env-status.wat");
+
+    #[tokio::test]
+    async fn test_send() -> anyhow::Result<()> {
+        let resp = TestHarness::from_file(ENV_STATUS)?.get("/").await?;
+        assert_eq!(resp.status(), 200);
+        assert!(resp.body().is_empty());
+
+        let harness = TestHarness::from_file(ENV_STATUS)?
+            .with_env("GREETING", "hello")
+            .with_env("TARGET", "world");
+        assert_eq!(harness.post("/", "ignored").await?.status(), 202);
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use bytes::Bytes;
+use hyper::HeaderMap;
+use serde::de::DeserializeOwned;
+
+/**
+A fully collected response returned by the guest.
+ */
+#[derive(Debug, Clone)]
+pub struct TestResponse {
+    status: u16,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl TestResponse {
+    pub(crate) fn new(status: u16, headers: HeaderMap, body: Bytes) -> Self {
+        TestResponse {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    pub fn text(&self) -> anyhow::Result<&str> {
+        Ok(std::str::from_utf8(&self.body)?)
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> anyhow::Result<T> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accessors() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", "application/json".parse().unwrap());
+        let resp = TestResponse::new(200, headers, Bytes::from_static(br#"{"a":1}"#));
+
+        assert_eq!(resp.header("Content-Type"), Some("application/json"));
+        assert_eq!(resp.text().unwrap(), r#"{"a":1}"#);
+        assert_eq!(resp.json::<serde_json::Value>().unwrap()["a"], 1);
+    }
+}
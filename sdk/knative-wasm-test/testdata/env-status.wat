@@ -0,0 +1,143 @@
+;; A minimal wasi-http guest for the harness tests. It answers every request
+;; with an empty response whose status is 200 plus the number of environment
+;; variables it sees, so the tests can tell the WASI CLI imports are linked
+;; and configured.
+(component
+  (import "wasi:cli/environment@0.2.0" (instance $environment
+    (export "get-environment" (func (result (list (tuple string string)))))
+  ))
+  (import "wasi:http/types@0.2.0" (instance $types
+    (export "fields" (type $fields (sub resource)))
+    (export "incoming-request" (type (sub resource)))
+    (export "outgoing-response" (type $outgoing-response (sub resource)))
+    (export "response-outparam" (type $response-outparam (sub resource)))
+    (type $dns-error-payload (record
+      (field "rcode" (option string))
+      (field "info-code" (option u16))))
+    (export "DNS-error-payload" (type $dns-error-payload-export (eq $dns-error-payload)))
+    (type $tls-alert-received-payload (record
+      (field "alert-id" (option u8))
+      (field "alert-message" (option string))))
+    (export "TLS-alert-received-payload"
+      (type $tls-alert-received-payload-export (eq $tls-alert-received-payload)))
+    (type $field-size-payload (record
+      (field "field-name" (option string))
+      (field "field-size" (option u32))))
+    (export "field-size-payload" (type $field-size-payload-export (eq $field-size-payload)))
+    (type $error-code (variant
+      (case "DNS-timeout")
+      (case "DNS-error" $dns-error-payload-export)
+      (case "destination-not-found")
+      (case "destination-unavailable")
+      (case "destination-IP-prohibited")
+      (case "destination-IP-unroutable")
+      (case "connection-refused")
+      (case "connection-terminated")
+      (case "connection-timeout")
+      (case "connection-read-timeout")
+      (case "connection-write-timeout")
+      (case "connection-limit-reached")
+      (case "TLS-protocol-error")
+      (case "TLS-certificate-error")
+      (case "TLS-alert-received" $tls-alert-received-payload-export)
+      (case "HTTP-request-denied")
+      (case "HTTP-request-length-required")
+      (case "HTTP-request-body-size" (option u64))
+      (case "HTTP-request-method-invalid")
+      (case "HTTP-request-URI-invalid")
+      (case "HTTP-request-URI-too-long")
+      (case "HTTP-request-header-section-size" (option u32))
+      (case "HTTP-request-header-size" (option $field-size-payload-export))
+      (case "HTTP-request-trailer-section-size" (option u32))
+      (case "HTTP-request-trailer-size" $field-size-payload-export)
+      (case "HTTP-response-incomplete")
+      (case "HTTP-response-header-section-size" (option u32))
+      (case "HTTP-response-header-size" $field-size-payload-export)
+      (case "HTTP-response-body-size" (option u64))
+      (case "HTTP-response-trailer-section-size" (option u32))
+      (case "HTTP-response-trailer-size" $field-size-payload-export)
+      (case "HTTP-response-transfer-coding" (option string))
+      (case "HTTP-response-content-coding" (option string))
+      (case "HTTP-response-timeout")
+      (case "HTTP-upgrade-failed")
+      (case "HTTP-protocol-error")
+      (case "loop-detected")
+      (case "configuration-error")
+      (case "internal-error" (option string))))
+    (export "error-code" (type $error-code-export (eq $error-code)))
+    (export "[constructor]fields" (func (result (own $fields))))
+    (export "[constructor]outgoing-response"
+      (func (param "headers" (own $fields)) (result (own $outgoing-response))))
+    (export "[method]outgoing-response.set-status-code"
+      (func (param "self" (borrow $outgoing-response)) (param "status-code" u16) (result (result))))
+    (export "[static]response-outparam.set"
+      (func (param "param" (own $response-outparam))
+            (param "response" (result (own $outgoing-response) (error $error-code-export)))))
+  ))
+
+  (alias export $types "incoming-request" (type $incoming-request))
+  (alias export $types "response-outparam" (type $response-outparam))
+
+  (core module $libc
+    (memory (export "memory") 1)
+    (global $heap (mut i32) (i32.const 1024))
+    ;; A bump allocator; every request gets a fresh instance.
+    (func (export "cabi_realloc") (param i32 i32 i32 i32) (result i32)
+      (local $ptr i32)
+      (local.set $ptr
+        (i32.and
+          (i32.add (global.get $heap) (i32.sub (local.get 2) (i32.const 1)))
+          (i32.sub (i32.const 0) (local.get 2))))
+      (global.set $heap (i32.add (local.get $ptr) (local.get 3)))
+      (local.get $ptr))
+  )
+  (core instance $libc (instantiate $libc))
+
+  (alias export $environment "get-environment" (func $get-environment))
+  (alias export $types "[constructor]fields" (func $new-fields))
+  (alias export $types "[constructor]outgoing-response" (func $new-response))
+  (alias export $types "[method]outgoing-response.set-status-code" (func $set-status-code))
+  (alias export $types "[static]response-outparam.set" (func $set-response))
+
+  (core func $get-environment (canon lower (func $get-environment)
+    (memory $libc "memory") (realloc (func $libc "cabi_realloc"))))
+  (core func $new-fields (canon lower (func $new-fields)))
+  (core func $new-response (canon lower (func $new-response)))
+  (core func $set-status-code (canon lower (func $set-status-code)))
+  (core func $set-response (canon lower (func $set-response) (memory $libc "memory")))
+
+  (core module $guest
+    (import "libc" "memory" (memory 1))
+    (import "host" "get-environment" (func $get-environment (param i32)))
+    (import "host" "new-fields" (func $new-fields (result i32)))
+    (import "host" "new-response" (func $new-response (param i32) (result i32)))
+    (import "host" "set-status-code" (func $set-status-code (param i32 i32) (result i32)))
+    (import "host" "set-response"
+      (func $set-response (param i32 i32 i32 i32 i64 i32 i32 i32 i32)))
+    (func (export "handle") (param $request i32) (param $response-out i32)
+      (local $response i32)
+      ;; The environment list lands at 0 as (ptr, len).
+      (call $get-environment (i32.const 0))
+      (local.set $response (call $new-response (call $new-fields)))
+      (drop (call $set-status-code
+        (local.get $response)
+        (i32.add (i32.const 200) (i32.load (i32.const 4)))))
+      (call $set-response
+        (local.get $response-out)
+        (i32.const 0) (local.get $response)
+        (i32.const 0) (i64.const 0) (i32.const 0) (i32.const 0) (i32.const 0) (i32.const 0)))
+  )
+  (core instance $guest (instantiate $guest
+    (with "libc" (instance $libc))
+    (with "host" (instance
+      (export "get-environment" (func $get-environment))
+      (export "new-fields" (func $new-fields))
+      (export "new-response" (func $new-response))
+      (export "set-status-code" (func $set-status-code))
+      (export "set-response" (func $set-response))))))
+
+  (func $handle (param "request" (own $incoming-request)) (param "response-out" (own $response-outparam))
+    (canon lift (core func $guest "handle")))
+  (instance $incoming-handler (export "handle" (func $handle)))
+  (export "wasi:http/incoming-handler@0.2.0" (instance $incoming-handler))
+)
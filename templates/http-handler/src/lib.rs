@@ -0,0 +1,23 @@
+use knative_wasm_sdk::{http_handler, Request, Response, Result, Router};
+
+#[http_handler]
+fn handle(req: Request) -> Result<Response> {
+    Router::new().get("/", hello).handle(req)
+}
+
+fn hello(req: Request) -> Result<Response> {
+    let name = req.query("name").unwrap_or("WASI");
+    Ok(Response::text(format!("Hello, {}!", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use knative_wasm_sdk::Method;
+
+    #[test]
+    fn test_hello() {
+        let resp = hello(Request::new(Method::Get, "/?name=Knative")).unwrap();
+        assert_eq!(resp.body(), b"Hello, Knative!");
+    }
+}